@@ -0,0 +1,235 @@
+use crate::commands::{CommandExecutionError, Day};
+use crate::config::{self, Config};
+use crate::reporting::{counted_work_duration, format_duration, load_available_days};
+use chrono::{Duration, NaiveDate};
+
+/// Expected and actually worked time over a range of days.
+#[derive(Debug, PartialEq)]
+pub struct Balance {
+    pub target: Duration,
+    pub worked: Duration,
+    pub absent_days: usize,
+}
+
+impl Balance {
+    #[must_use]
+    pub fn overtime(&self) -> Duration {
+        self.worked - self.target
+    }
+}
+
+/// Returns the report of the overtime balance from the configured start date until today.
+///
+/// # Errors
+///
+/// Fails if `balanceStartDate` or the target hours are not configured, or if the config cannot be loaded.
+pub fn report_balance(today: NaiveDate) -> Result<String, CommandExecutionError> {
+    let config = config::load_config()?;
+    let start = config
+        .balance_start_date
+        .ok_or(CommandExecutionError::NotConfigured(
+            "balanceStartDate".to_string(),
+        ))?;
+
+    if config.target_for(today).is_none() {
+        return Err(CommandExecutionError::NotConfigured(
+            "dailyTargetHours".to_string(),
+        ));
+    }
+
+    let dates = dates_between(start, today);
+    let days = load_available_days(&dates);
+
+    Ok(format!(
+        "# Overtime balance since {}
+
+{}",
+        start.format("%Y-%m-%d"),
+        format_balance(&compute_balance(&dates, &days, &config))
+    ))
+}
+
+/// Returns the overtime section of a report over the given dates.
+///
+/// Dates after today are ignored. Returns `None` if no target is configured.
+#[must_use]
+pub fn overtime_section(
+    dates: &[NaiveDate],
+    days: &[Day],
+    config: &Config,
+    today: NaiveDate,
+) -> Option<String> {
+    config.target_for(today)?;
+
+    let dates: Vec<NaiveDate> = dates
+        .iter()
+        .copied()
+        .filter(|date| *date <= today)
+        .collect();
+    let mut section = format!(
+        "## overtime
+
+{}",
+        format_balance(&compute_balance(&dates, days, config))
+    );
+
+    if let (Some(start), Some(end)) = (config.balance_start_date, dates.last()) {
+        let balance_dates = dates_between(start, *end);
+        let balance = compute_balance(&balance_dates, &load_available_days(&balance_dates), config);
+        section = format!(
+            "{section}\n- balance since {}: {}",
+            start.format("%Y-%m-%d"),
+            format_signed_duration(&balance.overtime())
+        );
+    }
+
+    Some(section)
+}
+
+/// Computes the balance over the given dates.
+///
+/// Days marked as absent do not count towards the target and are only counted as absent days
+/// if they have a target, a configured `breakDeduction` is applied.
+/// Expects the chunks of each day to be sorted by end time.
+#[must_use]
+pub fn compute_balance(dates: &[NaiveDate], days: &[Day], config: &Config) -> Balance {
+    let mut balance = Balance {
+        target: Duration::zero(),
+        worked: Duration::zero(),
+        absent_days: 0,
+    };
+
+    for date in dates {
+        let day = days.iter().find(|day| day.date == *date);
+
+        if day.is_some_and(|day| day.absence.is_some()) {
+            if config.target_for(*date).is_some() {
+                balance.absent_days += 1;
+            }
+            continue;
+        }
+
+        balance.target = balance.target + config.target_for(*date).unwrap_or(Duration::zero());
+        if let Some(day) = day {
+            balance.worked = balance.worked + counted_work_duration(day, config);
+        }
+    }
+
+    balance
+}
+
+fn format_balance(balance: &Balance) -> String {
+    format!(
+        "- target: {}
+- worked: {}
+- absent days: {}
+- overtime: {}",
+        format_duration(&balance.target),
+        format_duration(&balance.worked),
+        balance.absent_days,
+        format_signed_duration(&balance.overtime())
+    )
+}
+
+fn format_signed_duration(duration: &Duration) -> String {
+    if *duration < Duration::zero() {
+        format!("-{}", format_duration(&-*duration))
+    } else {
+        format!("+{}", format_duration(duration))
+    }
+}
+
+fn dates_between(start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    start.iter_days().take_while(|date| *date <= end).collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::rules::{BreakDeduction, Rule};
+    use crate::test_util::chunk_until;
+
+    #[cfg(test)]
+    use pretty_assertions::assert_eq;
+
+    fn day(date: NaiveDate, end_hour: u32) -> Day {
+        Day {
+            date,
+            ..crate::test_util::day(vec![chunk_until("kyoshi", end_hour, 0)])
+        }
+    }
+
+    #[test]
+    fn test_compute_balance() {
+        let config = Config {
+            daily_target_hours: Some(8.0),
+            ..Config::default()
+        };
+
+        // monday to sunday
+        let dates = dates_between(
+            NaiveDate::from_ymd_opt(2023, 11, 13).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 11, 19).unwrap(),
+        );
+        assert_eq!(dates.len(), 7);
+
+        let mut absent_day = day(dates[2], 8);
+        absent_day.chunks.clear();
+        absent_day.absence = Some("vacation".to_string());
+
+        let days = vec![
+            day(dates[0], 17),
+            day(dates[1], 16),
+            absent_day,
+            day(dates[3], 18),
+            day(dates[5], 10),
+        ];
+
+        let balance = compute_balance(&dates, &days, &config);
+
+        // friday is missing completely, saturday has no target
+        assert_eq!(
+            balance,
+            Balance {
+                target: Duration::hours(32),
+                worked: Duration::hours(29),
+                absent_days: 1,
+            }
+        );
+        assert_eq!(
+            "\
+- target: 32.00h (32h 0m)
+- worked: 29.00h (29h 0m)
+- absent days: 1
+- overtime: -3.00h (3h 0m)",
+            format_balance(&balance)
+        );
+
+        // without a target, there are no absent days
+        assert_eq!(
+            compute_balance(&dates, &days, &Config::default()).absent_days,
+            0
+        );
+    }
+
+    #[test]
+    fn test_compute_balance_break_deduction() {
+        let config = Config {
+            daily_target_hours: Some(8.0),
+            rules: vec![Rule::MinBreak {
+                after_hours: 6.0,
+                break_minutes: 30,
+                min_block_minutes: 0,
+            }],
+            break_deduction: Some(BreakDeduction::VirtualBreak),
+            ..Config::default()
+        };
+        let date = NaiveDate::from_ymd_opt(2023, 11, 13).unwrap();
+
+        // 9h without a break
+        let balance = compute_balance(&[date], &[day(date, 17)], &config);
+
+        assert_eq!(balance.worked, Duration::minutes(8 * 60 + 30));
+    }
+}
@@ -0,0 +1,1370 @@
+use crate::terminal::{self, PomodoroOptions};
+use chron::{
+    last_chunk_end, load_day, parse_duration, parse_time_input, DurationChunk, Error, GroupBy,
+    Period, ReportOptions, TimeInput, Violation,
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use clap::builder::PossibleValuesParser;
+use clap::error::ErrorKind;
+use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+#[derive(PartialEq, Debug)]
+pub enum Command {
+    Track {
+        project: String,
+        /// End of the chunk, now if not given.
+        at: Option<TimeInput>,
+        description: Option<String>,
+        tags: Vec<String>,
+    },
+    /// Chunks given by their durations, one after the other since the end of the last chunk.
+    TrackDurations(Vec<DurationChunk>),
+    Projects(ProjectsSubCommand),
+    Break(Option<String>),
+    CheckIn,
+    RetroTrack {
+        start_time: Option<TimeInput>,
+        end_time: TimeInput,
+        project: String,
+        description: Option<String>,
+        tags: Vec<String>,
+    },
+    Start {
+        project: String,
+        description: Option<String>,
+        tags: Vec<String>,
+    },
+    Stop,
+    Status {
+        short: bool,
+    },
+    Review,
+    Dashboard,
+    Remind,
+    Prompt,
+    Suggest {
+        source: SuggestSource,
+        yes: bool,
+    },
+    ApplySchedule(NaiveDate),
+    Pomodoro {
+        project: String,
+        description: Option<String>,
+        tags: Vec<String>,
+        options: PomodoroOptions,
+    },
+    Absent {
+        date: NaiveDate,
+        reason: Option<String>,
+    },
+    Balance,
+    Reset,
+    Report(Period, ReportOptions),
+    Export {
+        period: Period,
+        mark_exported: bool,
+    },
+    Serve {
+        port: u16,
+        token: Option<String>,
+        no_token: bool,
+    },
+    Completions(clap_complete::Shell),
+    Man,
+    Version,
+}
+
+/// Local data that chunks are proposed from.
+#[derive(PartialEq, Debug)]
+pub enum SuggestSource {
+    /// Commits in the repositories.
+    Git(Vec<PathBuf>),
+    /// Commands in the shell history, the configured or default one if no file is given.
+    History(Option<PathBuf>),
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ProjectsSubCommand {
+    Add(String),
+    Delete(String),
+    List,
+}
+
+/// Executes a parsed command.
+///
+/// # Errors
+///
+/// Fails with the error of the executed command.
+#[allow(clippy::too_many_lines)]
+pub fn execute_command(command: Command) -> Result<(), Error> {
+    let now = chrono::offset::Local::now().naive_local();
+
+    match command {
+        Command::CheckIn => chron::check_in(now),
+        Command::Track {
+            project,
+            at,
+            description,
+            tags,
+        } => {
+            let time = match at {
+                Some(at) => resolve_time(at, now)?,
+                None => now.time(),
+            };
+            print_warnings(&chron::track(now, None, time, project, description, tags)?);
+            Ok(())
+        }
+        Command::TrackDurations(chunks) => {
+            print_warnings(&chron::track_durations(now, chunks)?);
+            Ok(())
+        }
+        Command::Projects(subcommand) => match subcommand {
+            ProjectsSubCommand::Add(project) => chron::add_project(project),
+            ProjectsSubCommand::Delete(project) => chron::delete_project(&project),
+            ProjectsSubCommand::List => {
+                let config = chron::load_config()?;
+                println!("Projects:");
+                for project in config.projects {
+                    println!("  - {}", project.name);
+                }
+                Ok(())
+            }
+        },
+        Command::Break(description) => {
+            print_warnings(&chron::take_break(now, description)?);
+            Ok(())
+        }
+        Command::RetroTrack {
+            start_time,
+            end_time,
+            project,
+            description,
+            tags,
+        } => {
+            print_warnings(&chron::track(
+                now,
+                start_time
+                    .map(|start_time| resolve_time(start_time, now))
+                    .transpose()?,
+                resolve_time(end_time, now)?,
+                project,
+                description,
+                tags,
+            )?);
+            Ok(())
+        }
+        Command::Start {
+            project,
+            description,
+            tags,
+        } => chron::start(now, project, description, tags),
+        Command::Stop => chron::stop(now),
+        Command::Status { short } => {
+            let day = load_day(now.date())?;
+            let status = chron::compute_status(day, now.time(), &chron::load_config()?);
+            if short {
+                println!("{}", chron::format_short_status(&status));
+            } else {
+                println!("{}", chron::format_status(&status));
+            }
+            Ok(())
+        }
+        Command::Review => terminal::review(),
+        Command::Dashboard => crate::dashboard::dashboard(),
+        Command::Remind => terminal::remind(),
+        Command::ApplySchedule(date) => {
+            let applied = chron::apply_schedule(date, now)?;
+            if applied.chunks.is_empty() {
+                println!("Nothing is scheduled on {date}.");
+            }
+            for (recurring, result) in &applied.chunks {
+                let range = format!(
+                    "{}-{} {}",
+                    recurring.start.format("%H:%M"),
+                    recurring.end.format("%H:%M"),
+                    recurring.project
+                );
+                match result {
+                    Ok(()) => println!("Tracked {range}"),
+                    Err(skipped) => println!("Skipped {range}, {skipped}"),
+                }
+            }
+            print_warnings(&applied.warnings);
+            Ok(())
+        }
+        Command::Pomodoro {
+            project,
+            description,
+            tags,
+            options,
+        } => terminal::pomodoro(&project, description.as_deref(), &tags, &options),
+        Command::Suggest { source, yes } => match source {
+            SuggestSource::Git(repos) => {
+                terminal::track_proposals(chron::suggest_from_git(&repos, now)?, yes)
+            }
+            SuggestSource::History(file) => {
+                terminal::track_proposals(chron::suggest_from_history(file.as_deref(), now)?, yes)
+            }
+        },
+        Command::Prompt => {
+            println!("{}", chron::prompt(now));
+            Ok(())
+        }
+        Command::Absent { date, reason } => chron::absent(date, reason),
+        Command::Balance => {
+            println!("{}", chron::report_balance(now.date())?);
+            Ok(())
+        }
+        Command::Reset => chron::reset(now.date()),
+        Command::Report(period, options) => {
+            let report = match period {
+                Period::Day(date) => chron::report_day(date, &options)?,
+                Period::Week(date) => chron::report_week(date, now.date(), &options)?,
+                Period::Month(date) => chron::report_month(date, now.date(), &options)?,
+            };
+            println!("{report}");
+            Ok(())
+        }
+        Command::Export {
+            period,
+            mark_exported,
+        } => {
+            let export = chron::export_worklogs(&period, mark_exported)?;
+
+            // stdout is kept for the JSON, so it can be piped to the API
+            for warning in &export.warnings {
+                eprintln!("WARNING: {warning}");
+            }
+
+            if mark_exported {
+                println!("Marked {} chunks as exported", export.worklogs.len());
+            } else {
+                println!("{}", serde_json::to_string_pretty(&export.worklogs)?);
+            }
+            Ok(())
+        }
+        Command::Serve {
+            port,
+            token,
+            no_token,
+        } => crate::server::serve(port, token.as_deref(), no_token),
+        Command::Completions(shell) => print_completions(shell),
+        Command::Man => print_man_page(),
+        Command::Version => {
+            println!("chron version: {}", env!("GIT_VERSION"));
+            Ok(())
+        }
+    }
+}
+
+/// Resolves a time typed by the user against now and the chunks tracked today.
+fn resolve_time(input: TimeInput, now: NaiveDateTime) -> Result<NaiveTime, Error> {
+    let day = load_day(now.date())?;
+
+    input.resolve(now.time(), last_chunk_end(&day))
+}
+
+/// Prints the rules a day violates after tracking.
+pub fn print_warnings(warnings: &[Violation]) {
+    for warning in warnings {
+        println!("WARNING: {warning}");
+    }
+}
+
+/// Port of the HTTP API if none is given.
+pub const DEFAULT_PORT: u16 = 7373;
+
+/// Time tracking in chunks.
+#[derive(Parser)]
+#[command(
+    name = "chron",
+    version = env!("GIT_VERSION"),
+    arg_required_else_help = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Check in for today
+    CheckIn,
+    /// Track the work since the end of the previous chunk
+    Track {
+        project: String,
+        /// End of the chunk instead of now, e.g. 10:30, -15m or +45m
+        #[arg(long, value_parser = parse_time_input, allow_hyphen_values = true)]
+        at: Option<TimeInput>,
+        /// Duration of the chunk like 40m or 1h30m, ending that long after the previous chunk
+        #[arg(long = "for", value_parser = parse_duration_arg, conflicts_with = "at")]
+        duration: Option<Duration>,
+        /// A further chunk after this one, e.g. --then 'korra 20m standup'
+        #[arg(
+            long,
+            value_name = "PROJECT DURATION [DESCRIPTION]",
+            requires = "duration"
+        )]
+        then: Vec<String>,
+        #[command(flatten)]
+        details: ChunkDetails,
+    },
+    /// Track a break since the end of the previous chunk, or switch to a running break
+    Break { description: Vec<String> },
+    /// Track a forgotten chunk, ending at the given time or within a range like 09:30-10:00
+    Retrotrack {
+        /// A time like 10:30, 1030, 10:30am, noon, -15m, '15 minutes ago' or +45m
+        /// (after the end of the last chunk), or a range of two of them
+        #[arg(value_parser = parse_time_range, allow_hyphen_values = true)]
+        time: TimeRange,
+        project: String,
+        #[command(flatten)]
+        details: ChunkDetails,
+    },
+    /// Start a running chunk now, closing the running one
+    Start {
+        project: String,
+        #[command(flatten)]
+        details: ChunkDetails,
+    },
+    /// Close the running chunk
+    Stop,
+    /// Show the status of today
+    Status {
+        /// Print a single line, e.g. for shell prompts
+        #[arg(long)]
+        short: bool,
+    },
+    /// Fill in the untracked gaps of today interactively
+    Review,
+    /// Show a live dashboard of today and the week in the terminal
+    #[command(visible_alias = "ui")]
+    Dashboard,
+    /// Watch today's chunks and remind to track or to check in
+    Remind,
+    /// Print a fast single line for shell prompts like PS1 or starship
+    Prompt,
+    /// Track the recurring chunks of the schedule in the config
+    ApplySchedule {
+        /// 'today', 'yesterday', an offset like -3 or YYYY-MM-DD
+        #[arg(
+            value_parser = parse_day,
+            allow_negative_numbers = true,
+            default_value = "today"
+        )]
+        date: NaiveDate,
+    },
+    /// Count down work and break intervals, tracking a chunk at the end of each
+    Pomodoro {
+        project: String,
+        /// Length of the work intervals like 25m, instead of the configured one
+        #[arg(long, value_parser = parse_interval_arg)]
+        work: Option<Duration>,
+        /// Length of the breaks like 5m, instead of the configured one
+        #[arg(long = "break", value_parser = parse_interval_arg)]
+        pause: Option<Duration>,
+        /// Stop after this many work intervals
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        rounds: Option<u32>,
+        #[command(flatten)]
+        details: ChunkDetails,
+    },
+    /// Propose chunks from local data since the end of the last chunk
+    #[command(group(ArgGroup::new("source").required(true).args(["git", "history"])))]
+    Suggest {
+        /// Repositories whose commits of today are proposed as chunks
+        #[arg(long, num_args = 1..)]
+        git: Vec<PathBuf>,
+        /// Propose chunks for the untracked gaps from the shell history, the configured
+        /// or default one if no file is given
+        #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "")]
+        history: Option<String>,
+        /// Track all proposals without asking
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Mark a day without work, e.g. because of illness or vacation
+    Absent {
+        /// 'today', 'yesterday', an offset like -3 or YYYY-MM-DD
+        #[arg(value_parser = parse_day, allow_negative_numbers = true)]
+        date: NaiveDate,
+        reason: Vec<String>,
+    },
+    /// Show the overtime balance
+    Balance,
+    /// Delete the tracking of today
+    Reset,
+    /// Report the tracked time of a day, week or month
+    #[command(visible_alias = "rep")]
+    Report {
+        #[command(flatten)]
+        period: PeriodArgs,
+        /// Print line items of the billable projects instead of the log
+        #[arg(long)]
+        invoice: bool,
+        /// Additional grouping next to the summary per project
+        #[arg(long, value_enum, default_value_t)]
+        by: GroupByKind,
+        /// Maximum depth of sub-projects like kyoshi/backend in the summary
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+    /// Print Jira worklogs of the chunks with ticket references
+    Export {
+        #[command(flatten)]
+        period: PeriodArgs,
+        /// Record the chunks as exported instead of printing them, after posting the worklogs
+        #[arg(long)]
+        mark_exported: bool,
+    },
+    /// Serve the local HTTP API
+    Serve {
+        #[arg(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+        /// Token required as 'Authorization: Bearer <token>', generated if not given
+        #[arg(long)]
+        token: Option<String>,
+        /// Accept requests without a token, from any local process
+        #[arg(long, conflicts_with = "token")]
+        no_token: bool,
+    },
+    /// Manage the projects in the config file
+    Projects {
+        #[command(subcommand)]
+        command: ProjectsCliCommand,
+    },
+    /// Print shell completions, including the configured projects
+    Completions { shell: Shell },
+    /// Print the man page
+    Man,
+    /// Print the version
+    Version,
+}
+
+#[derive(Subcommand)]
+enum ProjectsCliCommand {
+    /// Add a project
+    #[command(visible_alias = "new")]
+    Add { name: String },
+    /// Delete a project, its tracked time stays in the reports
+    Delete { project: String },
+    /// List the projects
+    List,
+}
+
+#[derive(Args)]
+struct ChunkDetails {
+    /// Tag of the chunk, tags can also be written as #tag in the description
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    description: Vec<String>,
+}
+
+#[derive(Args)]
+struct PeriodArgs {
+    #[arg(value_enum, default_value_t = PeriodKind::Day)]
+    period: PeriodKind,
+    /// An offset like -1 or YYYY-MM-DD, today if not given
+    #[arg(allow_negative_numbers = true)]
+    date: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum PeriodKind {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(ValueEnum, Clone, Copy, Default)]
+enum GroupByKind {
+    #[default]
+    Project,
+    Tag,
+    Ticket,
+}
+
+impl From<GroupByKind> for GroupBy {
+    fn from(kind: GroupByKind) -> Self {
+        match kind {
+            GroupByKind::Project => GroupBy::Project,
+            GroupByKind::Tag => GroupBy::Tag,
+            GroupByKind::Ticket => GroupBy::Ticket,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct TimeRange {
+    start: Option<TimeInput>,
+    end: TimeInput,
+}
+
+impl Cli {
+    /// Turns the parsed arguments into the command to execute.
+    #[allow(clippy::too_many_lines)]
+    fn into_command(self) -> Result<Command, clap::Error> {
+        let command = match self.command {
+            CliCommand::CheckIn => Command::CheckIn,
+            CliCommand::Track {
+                project,
+                duration: Some(duration),
+                then,
+                details,
+                ..
+            } => {
+                let mut chunks = vec![DurationChunk {
+                    project,
+                    duration,
+                    description: join_description(&details.description),
+                    tags: details.tags,
+                }];
+                for chunk in then {
+                    chunks.push(parse_then(&chunk)?);
+                }
+                Command::TrackDurations(chunks)
+            }
+            CliCommand::Track {
+                project,
+                at,
+                details,
+                ..
+            } => Command::Track {
+                project,
+                at,
+                description: join_description(&details.description),
+                tags: details.tags,
+            },
+            CliCommand::Break { description } => Command::Break(join_description(&description)),
+            CliCommand::Retrotrack {
+                time,
+                project,
+                details,
+            } => Command::RetroTrack {
+                start_time: time.start,
+                end_time: time.end,
+                project,
+                description: join_description(&details.description),
+                tags: details.tags,
+            },
+            CliCommand::Start { project, details } => Command::Start {
+                project,
+                description: join_description(&details.description),
+                tags: details.tags,
+            },
+            CliCommand::Stop => Command::Stop,
+            CliCommand::Status { short } => Command::Status { short },
+            CliCommand::Review => Command::Review,
+            CliCommand::Dashboard => Command::Dashboard,
+            CliCommand::Remind => Command::Remind,
+            CliCommand::Prompt => Command::Prompt,
+            CliCommand::ApplySchedule { date } => Command::ApplySchedule(date),
+            CliCommand::Pomodoro {
+                project,
+                work,
+                pause,
+                rounds,
+                details,
+            } => Command::Pomodoro {
+                project,
+                description: join_description(&details.description),
+                tags: details.tags,
+                options: PomodoroOptions {
+                    work,
+                    pause,
+                    rounds,
+                },
+            },
+            CliCommand::Suggest { git, history, yes } => Command::Suggest {
+                source: match history {
+                    Some(file) => SuggestSource::History(
+                        Some(file)
+                            .filter(|file| !file.is_empty())
+                            .map(PathBuf::from),
+                    ),
+                    None => SuggestSource::Git(git),
+                },
+                yes,
+            },
+            CliCommand::Absent { date, reason } => Command::Absent {
+                date,
+                reason: join_description(&reason),
+            },
+            CliCommand::Balance => Command::Balance,
+            CliCommand::Reset => Command::Reset,
+            CliCommand::Report {
+                period,
+                invoice,
+                by,
+                depth,
+            } => Command::Report(
+                period.into_period()?,
+                ReportOptions {
+                    invoice,
+                    group_by: by.into(),
+                    depth,
+                },
+            ),
+            CliCommand::Export {
+                period,
+                mark_exported,
+            } => Command::Export {
+                period: period.into_period()?,
+                mark_exported,
+            },
+            CliCommand::Serve {
+                port,
+                token,
+                no_token,
+            } => Command::Serve {
+                port,
+                token,
+                no_token,
+            },
+            CliCommand::Projects { command } => Command::Projects(match command {
+                ProjectsCliCommand::Add { name } => ProjectsSubCommand::Add(name),
+                ProjectsCliCommand::Delete { project } => ProjectsSubCommand::Delete(project),
+                ProjectsCliCommand::List => ProjectsSubCommand::List,
+            }),
+            CliCommand::Completions { shell } => Command::Completions(shell),
+            CliCommand::Man => Command::Man,
+            CliCommand::Version => Command::Version,
+        };
+
+        Ok(command)
+    }
+}
+
+impl PeriodArgs {
+    fn into_period(self) -> Result<Period, clap::Error> {
+        let error = |actual: &str| {
+            Cli::command().error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "Your date input '{actual}' does not match expected format 'offset' or 'YYYY-MM-DD'"
+                ),
+            )
+        };
+
+        let date = self.date.unwrap_or("0".to_string());
+        let today = chrono::offset::Local::now().date_naive();
+
+        // first try parsing number (offset like -3), then parse as concrete date
+        match (self.period, date.parse::<i64>()) {
+            (PeriodKind::Day, _) => parse_day(&date).map(Period::Day).map_err(|_| error(&date)),
+            (PeriodKind::Week, Ok(offset)) => {
+                Ok(Period::Week(today + chrono::Duration::weeks(offset)))
+            }
+            (PeriodKind::Month, Ok(offset)) => {
+                println!("WARNING: This method might not be accurate, since it assumes a month to have 30 days!\n");
+                Ok(Period::Month(today + chrono::Duration::days(offset * 30)))
+            }
+            (period, Err(_)) => {
+                let date =
+                    NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|_| error(&date))?;
+                Ok(match period {
+                    PeriodKind::Week => Period::Week(date),
+                    _ => Period::Month(date),
+                })
+            }
+        }
+    }
+}
+
+/// Parses the command line arguments, the first one being the name of the binary.
+///
+/// # Errors
+///
+/// Fails if the command is unknown or its parameters are missing or invalid,
+/// and for `--help` and `--version`, whose output is the message of the error.
+pub fn parse_command(arguments: &[String]) -> Result<Command, clap::Error> {
+    Cli::try_parse_from(arguments)?.into_command()
+}
+
+fn join_description(words: &[String]) -> Option<String> {
+    Some(words.join(" ")).filter(|description| !description.is_empty())
+}
+
+/// Parses a day given as 'today', 'yesterday', an offset like -3 or a concrete date.
+fn parse_day(argument: &str) -> Result<NaiveDate, String> {
+    let day_arg = match argument {
+        "today" => "0",
+        "yesterday" => "-1",
+        _ => argument,
+    };
+
+    // first try parsing number (offset like -3), then parse as concrete date
+    match day_arg.parse::<i64>() {
+        Ok(offset) => {
+            Ok(chrono::offset::Local::now().date_naive() + chrono::Duration::days(offset))
+        }
+        Err(_) => NaiveDate::parse_from_str(day_arg, "%Y-%m-%d").map_err(|_| {
+            format!("Your date input '{day_arg}' does not match expected format 'offset' or 'YYYY-MM-DD'")
+        }),
+    }
+}
+
+/// Parses a duration like 40m or 1h30m.
+fn parse_duration_arg(argument: &str) -> Result<Duration, String> {
+    parse_duration(argument).ok_or_else(|| {
+        format!("Your duration input '{argument}' does not match a duration like '40m' or '1h30m'")
+    })
+}
+
+/// Parses the length of a pomodoro interval, which must be at least a minute.
+fn parse_interval_arg(argument: &str) -> Result<Duration, String> {
+    let duration = parse_duration_arg(argument)?;
+    if duration < Duration::minutes(1) {
+        return Err(format!(
+            "The interval '{argument}' must be at least a minute long"
+        ));
+    }
+    Ok(duration)
+}
+
+/// Parses the value of `--then`, a project, a duration and optionally a description.
+fn parse_then(chunk: &str) -> Result<DurationChunk, clap::Error> {
+    let error = |message: String| Cli::command().error(ErrorKind::ValueValidation, message);
+
+    let words: Vec<String> = chunk.split_whitespace().map(str::to_string).collect();
+    let [project, duration, description @ ..] = words.as_slice() else {
+        return Err(error(
+            "--then expects a project and a duration like 'korra 20m'".to_string(),
+        ));
+    };
+
+    Ok(DurationChunk {
+        project: project.clone(),
+        duration: parse_duration_arg(duration).map_err(error)?,
+        description: join_description(description),
+        tags: vec![],
+    })
+}
+
+/// Parses either only the end time (10:00) or a range with explicit start (09:30-10:00).
+fn parse_time_range(argument: &str) -> Result<TimeRange, String> {
+    match argument.split_once('-') {
+        Some((start, end)) if !start.is_empty() => Ok(TimeRange {
+            start: Some(parse_time_input(start)?),
+            end: parse_time_input(end)?,
+        }),
+        _ => Ok(TimeRange {
+            start: None,
+            end: parse_time_input(argument)?,
+        }),
+    }
+}
+
+/// Prints the completions for the shell, completing the configured projects where a
+/// project is expected.
+///
+/// # Errors
+///
+/// Fails if the config cannot be loaded.
+pub fn print_completions(shell: Shell) -> Result<(), Error> {
+    let projects: Vec<String> = chron::load_config()?
+        .projects
+        .into_iter()
+        .map(|project| project.name)
+        .collect();
+
+    let mut command = with_project_values(Cli::command(), &projects);
+    clap_complete::generate(shell, &mut command, "chron", &mut std::io::stdout());
+
+    Ok(())
+}
+
+/// Restricts all `project` arguments to the given projects.
+fn with_project_values(command: clap::Command, projects: &[String]) -> clap::Command {
+    let subcommands: Vec<String> = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect();
+
+    let mut command = command.mut_args(|arg| {
+        if arg.get_id() == "project" {
+            arg.value_parser(PossibleValuesParser::new(projects.to_vec()))
+        } else {
+            arg
+        }
+    });
+
+    for subcommand in subcommands {
+        command = command.mut_subcommand(subcommand, |subcommand| {
+            with_project_values(subcommand, projects)
+        });
+    }
+
+    command
+}
+
+/// Prints the man page.
+///
+/// # Errors
+///
+/// Fails if the man page cannot be written to stdout.
+pub fn print_man_page() -> Result<(), Error> {
+    clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::manual_string_new)]
+mod tests {
+    use super::*;
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(std::string::ToString::to_string).collect()
+    }
+
+    fn parse(args: &[String]) -> Result<Command, ErrorKind> {
+        parse_command(args).map_err(|e| e.kind())
+    }
+
+    #[test]
+    fn test_parse_invalid_and_missing_and_version() {
+        let args = to_args(&[""]);
+        assert_eq!(
+            parse(&args),
+            Err(ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand)
+        );
+
+        let args = to_args(&["", "invalid"]);
+        assert_eq!(parse(&args), Err(ErrorKind::InvalidSubcommand));
+
+        let args = to_args(&["", "version"]);
+        assert_eq!(parse(&args), Ok(Command::Version));
+    }
+
+    #[test]
+    fn test_parse_track() {
+        let args = to_args(&["", "track"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "track", "project"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Track {
+                project: "project".to_string(),
+                at: None,
+                description: None,
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&["", "track", "project", "a", "description"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Track {
+                project: "project".to_string(),
+                at: None,
+                description: Some("a description".to_string()),
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&[
+            "", "track", "project", "--tag", "review", "a", "#meeting", "--tag", "x",
+        ]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Track {
+                project: "project".to_string(),
+                at: None,
+                description: Some("a #meeting".to_string()),
+                tags: vec!["review".to_string(), "x".to_string()],
+            })
+        );
+
+        let args = to_args(&["", "track", "project", "--at", "-15m", "a"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Track {
+                project: "project".to_string(),
+                at: Some(TimeInput::Ago(chrono::Duration::minutes(15))),
+                description: Some("a".to_string()),
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&["", "track", "project", "--tag"]);
+        assert_eq!(parse(&args), Err(ErrorKind::InvalidValue));
+
+        let args = to_args(&["", "break"]);
+        assert_eq!(parse(&args), Ok(Command::Break(None)));
+
+        let args = to_args(&["", "break", "a", "description"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Break(Some("a description".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_parse_track_durations() {
+        let args = to_args(&[
+            "",
+            "track",
+            "kyoshi",
+            "--for",
+            "40m",
+            "review",
+            "--then",
+            "korra 1h5m standup #meeting",
+            "--then",
+            "break 10m",
+        ]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::TrackDurations(vec![
+                DurationChunk {
+                    project: "kyoshi".to_string(),
+                    duration: Duration::minutes(40),
+                    description: Some("review".to_string()),
+                    tags: vec![],
+                },
+                DurationChunk {
+                    project: "korra".to_string(),
+                    duration: Duration::minutes(65),
+                    description: Some("standup #meeting".to_string()),
+                    tags: vec![],
+                },
+                DurationChunk {
+                    project: "break".to_string(),
+                    duration: Duration::minutes(10),
+                    description: None,
+                    tags: vec![],
+                },
+            ]))
+        );
+
+        let args = to_args(&["", "track", "kyoshi", "--for", "40 parsecs"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ValueValidation));
+
+        let args = to_args(&["", "track", "kyoshi", "--for", "40m", "--at", "10:00"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ArgumentConflict));
+
+        let args = to_args(&["", "track", "kyoshi", "--then", "korra 20m"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "track", "kyoshi", "--for", "40m", "--then", "korra"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ValueValidation));
+    }
+
+    #[test]
+    fn test_parse_suggest() {
+        let args = to_args(&["", "suggest"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "suggest", "--git", "a", "b", "--yes"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Suggest {
+                source: SuggestSource::Git(vec![PathBuf::from("a"), PathBuf::from("b")]),
+                yes: true,
+            })
+        );
+
+        let args = to_args(&["", "suggest", "--history"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Suggest {
+                source: SuggestSource::History(None),
+                yes: false,
+            })
+        );
+
+        let args = to_args(&["", "suggest", "--history", "h.txt"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Suggest {
+                source: SuggestSource::History(Some(PathBuf::from("h.txt"))),
+                yes: false,
+            })
+        );
+
+        let args = to_args(&["", "suggest", "--history", "--git", "a"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ArgumentConflict));
+    }
+
+    #[test]
+    fn test_parse_apply_schedule() {
+        let args = to_args(&["", "apply-schedule"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::ApplySchedule(
+                chrono::offset::Local::now().date_naive()
+            ))
+        );
+
+        let args = to_args(&["", "apply-schedule", "2023-11-20"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::ApplySchedule(
+                NaiveDate::from_ymd_opt(2023, 11, 20).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_pomodoro() {
+        let args = to_args(&[
+            "", "pomodoro", "kyoshi", "--work", "50m", "--break", "10m", "write", "docs",
+        ]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Pomodoro {
+                project: "kyoshi".to_string(),
+                description: Some("write docs".to_string()),
+                tags: vec![],
+                options: PomodoroOptions {
+                    work: Some(Duration::minutes(50)),
+                    pause: Some(Duration::minutes(10)),
+                    rounds: None,
+                },
+            })
+        );
+
+        let args = to_args(&["", "pomodoro", "kyoshi", "--rounds", "2"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Pomodoro {
+                project: "kyoshi".to_string(),
+                description: None,
+                tags: vec![],
+                options: PomodoroOptions {
+                    rounds: Some(2),
+                    ..PomodoroOptions::default()
+                },
+            })
+        );
+
+        let args = to_args(&["", "pomodoro", "kyoshi", "--rounds", "0"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ValueValidation));
+
+        let args = to_args(&["", "pomodoro", "kyoshi", "--work", "0m"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ValueValidation));
+    }
+
+    #[test]
+    fn test_parse_projects() {
+        let args = to_args(&["", "projects"]);
+        assert_eq!(
+            parse(&args),
+            Err(ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand)
+        );
+
+        let args = to_args(&["", "projects", "add"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "projects", "add", "project"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Projects(ProjectsSubCommand::Add(
+                "project".to_string()
+            )))
+        );
+
+        let args = to_args(&["", "projects", "delete"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "projects", "delete", "project"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Projects(ProjectsSubCommand::Delete(
+                "project".to_string()
+            )))
+        );
+
+        let args = to_args(&["", "projects", "invalid"]);
+        assert_eq!(parse(&args), Err(ErrorKind::InvalidSubcommand));
+
+        let args = to_args(&["", "projects", "list"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Projects(ProjectsSubCommand::List))
+        );
+    }
+
+    #[test]
+    fn test_parse_start_stop_status() {
+        let args = to_args(&["", "start"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "start", "project", "a", "description"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Start {
+                project: "project".to_string(),
+                description: Some("a description".to_string()),
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&["", "stop"]);
+        assert_eq!(parse(&args), Ok(Command::Stop));
+
+        let args = to_args(&["", "status"]);
+        assert_eq!(parse(&args), Ok(Command::Status { short: false }));
+
+        let args = to_args(&["", "status", "--short"]);
+        assert_eq!(parse(&args), Ok(Command::Status { short: true }));
+    }
+
+    #[test]
+    fn test_parse_check_in_and_reset() {
+        let args = to_args(&["", "check-in"]);
+        assert_eq!(parse(&args), Ok(Command::CheckIn));
+
+        let args = to_args(&["", "reset"]);
+        assert_eq!(parse(&args), Ok(Command::Reset));
+    }
+
+    #[test]
+    fn test_parse_retrotrack() {
+        let args = to_args(&["", "retrotrack"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "retrotrack", "10:00"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "retrotrack", "10:00", "project"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::RetroTrack {
+                start_time: None,
+                end_time: TimeInput::At(NaiveTime::parse_from_str("10:00", "%H:%M").unwrap()),
+                project: "project".to_string(),
+                description: None,
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&["", "retrotrack", "10:00", "project", "a", "description"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::RetroTrack {
+                start_time: None,
+                end_time: TimeInput::At(NaiveTime::parse_from_str("10:00", "%H:%M").unwrap()),
+                project: "project".to_string(),
+                description: Some("a description".to_string()),
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&["", "retrotrack", "09:30-10:00", "project"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::RetroTrack {
+                start_time: Some(TimeInput::At(
+                    NaiveTime::parse_from_str("09:30", "%H:%M").unwrap()
+                )),
+                end_time: TimeInput::At(NaiveTime::parse_from_str("10:00", "%H:%M").unwrap()),
+                project: "project".to_string(),
+                description: None,
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&["", "retrotrack", "-15m", "project"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::RetroTrack {
+                start_time: None,
+                end_time: TimeInput::Ago(chrono::Duration::minutes(15)),
+                project: "project".to_string(),
+                description: None,
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&["", "retrotrack", "10:00-+45m", "project"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::RetroTrack {
+                start_time: Some(TimeInput::At(
+                    NaiveTime::parse_from_str("10:00", "%H:%M").unwrap()
+                )),
+                end_time: TimeInput::AfterLastChunk(chrono::Duration::minutes(45)),
+                project: "project".to_string(),
+                description: None,
+                tags: vec![],
+            })
+        );
+
+        let args = to_args(&["", "retrotrack", "09:30-10", "project"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ValueValidation));
+    }
+
+    #[test]
+    fn test_parse_absent_and_balance() {
+        let args = to_args(&["", "absent"]);
+        assert_eq!(parse(&args), Err(ErrorKind::MissingRequiredArgument));
+
+        let args = to_args(&["", "absent", "2023-11-17", "vacation"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Absent {
+                date: NaiveDate::from_ymd_opt(2023, 11, 17).unwrap(),
+                reason: Some("vacation".to_string()),
+            })
+        );
+
+        let args = to_args(&["", "absent", "today"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Absent {
+                date: chrono::offset::Local::now().date_naive(),
+                reason: None,
+            })
+        );
+
+        let args = to_args(&["", "balance"]);
+        assert_eq!(parse(&args), Ok(Command::Balance));
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let args = to_args(&["", "report"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Report(
+                Period::Day(chrono::offset::Local::now().date_naive()),
+                ReportOptions::default()
+            ))
+        );
+
+        let args = to_args(&["", "report", "month", "--invoice", "2023-11-17"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Report(
+                Period::Month(NaiveDate::from_ymd_opt(2023, 11, 17).unwrap()),
+                ReportOptions {
+                    invoice: true,
+                    ..ReportOptions::default()
+                }
+            ))
+        );
+
+        let args = to_args(&["", "report", "week", "--by", "tag"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Report(
+                Period::Week(chrono::offset::Local::now().date_naive()),
+                ReportOptions {
+                    group_by: GroupBy::Tag,
+                    ..ReportOptions::default()
+                }
+            ))
+        );
+
+        let args = to_args(&["", "report", "day", "--by", "ticket"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Report(
+                Period::Day(chrono::offset::Local::now().date_naive()),
+                ReportOptions {
+                    group_by: GroupBy::Ticket,
+                    ..ReportOptions::default()
+                }
+            ))
+        );
+
+        let args = to_args(&["", "report", "--depth", "1", "month"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Report(
+                Period::Month(chrono::offset::Local::now().date_naive()),
+                ReportOptions {
+                    depth: Some(1),
+                    ..ReportOptions::default()
+                }
+            ))
+        );
+
+        let args = to_args(&["", "report", "week", "--by"]);
+        assert_eq!(parse(&args), Err(ErrorKind::InvalidValue));
+
+        let args = to_args(&["", "report", "week", "--invalid"]);
+        assert_eq!(parse(&args), Err(ErrorKind::UnknownArgument));
+    }
+
+    #[test]
+    fn test_parse_export() {
+        let args = to_args(&["", "export", "week", "2023-11-20"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Export {
+                period: Period::Week(NaiveDate::from_ymd_opt(2023, 11, 20).unwrap()),
+                mark_exported: false,
+            })
+        );
+
+        let args = to_args(&["", "export", "--mark-exported"]);
+        assert!(matches!(
+            parse(&args),
+            Ok(Command::Export {
+                mark_exported: true,
+                ..
+            })
+        ));
+
+        let args = to_args(&["", "export", "year"]);
+        assert_eq!(parse(&args), Err(ErrorKind::InvalidValue));
+    }
+
+    #[test]
+    fn test_parse_serve() {
+        let args = to_args(&["", "serve"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Serve {
+                port: DEFAULT_PORT,
+                token: None,
+                no_token: false,
+            })
+        );
+
+        let args = to_args(&["", "serve", "--token", "secret", "--port", "8080"]);
+        assert_eq!(
+            parse(&args),
+            Ok(Command::Serve {
+                port: 8080,
+                token: Some("secret".to_string()),
+                no_token: false,
+            })
+        );
+
+        let args = to_args(&["", "serve", "--no-token"]);
+        assert!(matches!(
+            parse(&args),
+            Ok(Command::Serve { no_token: true, .. })
+        ));
+
+        let args = to_args(&["", "serve", "--no-token", "--token", "secret"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ArgumentConflict));
+
+        let args = to_args(&["", "serve", "--port", "http"]);
+        assert_eq!(parse(&args), Err(ErrorKind::ValueValidation));
+    }
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_with_project_values() {
+        let command = with_project_values(Cli::command(), &["kyoshi".to_string()]);
+
+        assert!(command
+            .clone()
+            .try_get_matches_from(["chron", "track", "kyoshi"])
+            .is_ok());
+        assert!(command
+            .try_get_matches_from(["chron", "projects", "delete", "korra"])
+            .is_err());
+    }
+}
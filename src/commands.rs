@@ -1,257 +1,50 @@
-use crate::config;
+use crate::config::{self, Config};
 use crate::file_handling;
-use chrono::{NaiveDate, NaiveTime};
+use crate::reporting::{day_spans, UNTRACKED};
+use crate::rules::{check_day, Violation};
+use crate::tags::{collect_tags, explicit_tags};
+use crate::tickets::extract_tickets;
+use crate::time_input::last_chunk_end;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 use std::fs;
 
+/// A chunk given by its duration instead of its end.
 #[derive(PartialEq, Debug)]
-pub enum Command {
-    Track {
-        project: String,
-        description: Option<String>,
-    },
-    Projects(ProjectsSubCommand),
-    Break(Option<String>),
-    CheckIn,
-    RetroTrack {
-        end_time: NaiveTime,
-        project: String,
-        description: Option<String>,
-    },
-    Reset,
-    Report(ReportSubCommand),
-    Version,
-}
-
-#[derive(PartialEq, Debug)]
-pub enum ProjectsSubCommand {
-    Add(String),
-    Delete(String),
-    List,
+pub struct DurationChunk {
+    pub project: String,
+    pub duration: Duration,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
 }
 
+/// The days a report or export covers, given by a date within them.
 #[derive(PartialEq, Debug)]
-pub enum ReportSubCommand {
+pub enum Period {
     Day(NaiveDate),
     Week(NaiveDate),
     Month(NaiveDate),
 }
 
-pub fn execute_command(command: Command) -> Result<(), CommandExecutionError> {
-    match command {
-        Command::CheckIn => check_in(),
-        Command::Track {
-            project,
-            description,
-        } => {
-            let now = chrono::offset::Local::now();
-            track(now.time(), project, description)
-        }
-        Command::Projects(subcommand) => match subcommand {
-            ProjectsSubCommand::Add(project) => config::add_project(project),
-            ProjectsSubCommand::Delete(project) => config::delete_project(project),
-            ProjectsSubCommand::List => {
-                let config = config::load_config()?;
-                println!("Projects:");
-                for project in config.projects {
-                    println!("  - {project}");
-                }
-                Ok(())
-            }
-        },
-        Command::Break(description) => {
-            let now = chrono::offset::Local::now();
-            track(now.time(), "break".to_string(), description)
-        }
-        Command::RetroTrack {
-            end_time,
-            project,
-            description,
-        } => track(end_time, project, description),
-        Command::Reset => reset(),
-        Command::Report(subcommand) => match subcommand {
-            ReportSubCommand::Day(date) => crate::reporting::report_day(date),
-            ReportSubCommand::Week(date) => {
-                crate::reporting::report_week(date);
-                Ok(())
-            }
-            ReportSubCommand::Month(date) => {
-                crate::reporting::report_month(date);
-                Ok(())
-            }
-        },
-        Command::Version => {
-            println!("chron version: {}", env!("GIT_VERSION"));
-            Ok(())
-        }
-    }
-}
-
-#[derive(Debug, PartialEq)]
-pub enum ParseCmdError {
-    NoCommand,
-    InvalidCommand(String),
-    MissingParameter(String),
-    InvalidTimeFormat(String),
-    InvalidDateFormat { expected: String, actual: String },
-}
-
-fn parse_project(cmd_name: &str, arguments: Option<&String>) -> Result<String, ParseCmdError> {
-    arguments
-        .ok_or(ParseCmdError::MissingParameter(format!(
-            "The command '{cmd_name}' requires a parameter 'project'"
-        )))
-        .map(std::clone::Clone::clone)
-}
-
-fn parse_description(arguments: Option<&[String]>) -> Option<String> {
-    arguments.filter(|a| !a.is_empty()).map(|a| a.join(" "))
+#[derive(PartialEq, Debug, Default)]
+pub struct ReportOptions {
+    /// Print line items of the billable projects instead of the log.
+    pub invoice: bool,
+    /// Additional grouping next to the summary per project.
+    pub group_by: GroupBy,
+    /// Maximum depth of sub-projects like `kyoshi/backend` in the summary.
+    pub depth: Option<usize>,
 }
 
-#[allow(clippy::too_many_lines)]
-pub fn parse_command(arguments: Vec<String>) -> Result<Command, ParseCmdError> {
-    if arguments.len() == 1 {
-        return Err(ParseCmdError::NoCommand);
-    }
-    match arguments
-        .get(1)
-        .expect("should always return Some(), because of the .len() check before")
-        .as_str()
-    {
-        "track" => {
-            let project = parse_project("track", arguments.get(2))?;
-            let description = parse_description(arguments.get(3..));
-            Ok(Command::Track {
-                project,
-                description,
-            })
-        }
-        "projects" => {
-            let subcommand = arguments.get(2).ok_or(ParseCmdError::MissingParameter(
-                "The command 'projects' requires a subcommand: chron projects <subcommand>"
-                    .to_string(),
-            ))?;
-            match subcommand.as_str() {
-                "add" => {
-                    let project = parse_project("projects add", arguments.get(3))?;
-                    Ok(Command::Projects(ProjectsSubCommand::Add(project)))
-                }
-                "delete" => {
-                    let project = parse_project("projects delete", arguments.get(3))?;
-                    Ok(Command::Projects(ProjectsSubCommand::Delete(project)))
-                }
-                "list" => Ok(Command::Projects(ProjectsSubCommand::List)),
-                _ => Err(ParseCmdError::InvalidCommand(format!(
-                    "projects {subcommand}"
-                ))),
-            }
-        }
-        "break" => {
-            let description = parse_description(arguments.get(2..));
-            Ok(Command::Break(description))
-        }
-        "check-in" => Ok(Command::CheckIn),
-        "retrotrack" => {
-            let end_time = arguments.get(2).ok_or(ParseCmdError::MissingParameter(
-                "The command 'retrotrack' requires a parameter 'end_time'".to_string(),
-            ))?;
-
-            let project = parse_project("retrotrack", arguments.get(3))?;
-            let description = parse_description(arguments.get(4..));
-
-            Ok(Command::RetroTrack {
-                end_time: NaiveTime::parse_from_str(&end_time, "%H:%M")
-                    .map_err(|_| ParseCmdError::InvalidTimeFormat(end_time.clone()))?,
-                project,
-                description,
-            })
-        }
-        "reset" => Ok(Command::Reset),
-        "report" | "rep" => {
-            let subcommand = arguments.get(2).map_or("day".to_string(), String::clone);
-
-            match subcommand.as_str() {
-                "day" => {
-                    let day_arg =
-                        arguments
-                            .get(3)
-                            .map_or("0".to_string(), |arg| match arg.as_str() {
-                                "today" => "0".to_string(),
-                                "yesterday" => "-1".to_string(),
-                                _ => arg.clone(),
-                            });
-
-                    // first try parsing number (offset like -3), then parse as concrete date
-                    let date = match day_arg.parse::<i64>() {
-                        Ok(offset) => {
-                            chrono::offset::Local::now().date_naive()
-                                + chrono::Duration::days(offset)
-                        }
-                        Err(_) => {
-                            NaiveDate::parse_from_str(&day_arg, "%Y-%m-%d").map_err(|_| {
-                                ParseCmdError::InvalidDateFormat {
-                                    expected: "'offset' or 'YYYY-MM-DD'".to_string(),
-                                    actual: day_arg.clone(),
-                                }
-                            })?
-                        }
-                    };
-
-                    Ok(Command::Report(ReportSubCommand::Day(date)))
-                }
-                "week" => {
-                    let week_arg = arguments.get(3).map_or("0".to_string(), String::clone);
-
-                    let date = week_arg
-                        .parse::<i64>()
-                        .map(|number| {
-                            chrono::offset::Local::now().date_naive()
-                                + chrono::Duration::weeks(number)
-                        })
-                        .or_else(|_| {
-                            NaiveDate::parse_from_str(&week_arg, "%Y-%m-%d").map_err(|_| {
-                                ParseCmdError::InvalidDateFormat {
-                                    expected: "'offset' or 'YYYY-MM-DD'".to_string(),
-                                    actual: week_arg.clone(),
-                                }
-                            })
-                        })?;
-
-                    Ok(Command::Report(ReportSubCommand::Week(date)))
-                }
-                "month" => {
-                    let month_arg = arguments.get(3).map_or("0".to_string(), String::clone);
-
-                    let date = month_arg
-                        .parse::<i64>()
-                        .map(|number| {
-                            println!("WARNING: This method might not be accurate, since it assumes a month to have 30 days!\n");
-
-                            chrono::offset::Local::now().date_naive()
-                                + chrono::Duration::days(number * 30)
-                        })
-                        .or_else(|_| {
-                            NaiveDate::parse_from_str(&month_arg, "%Y-%m-%d").map_err(|_| {
-                                ParseCmdError::InvalidDateFormat {
-                                    expected: "'offset' or 'YYYY-MM-DD'"
-                                        .to_string(),
-                                    actual: month_arg.clone(),
-                                }
-                            })
-                        })?;
-
-                    Ok(Command::Report(ReportSubCommand::Month(date)))
-                }
-                _ => Err(ParseCmdError::InvalidCommand(format!(
-                    "report {subcommand}"
-                ))),
-            }
-        }
-        "version" => Ok(Command::Version),
-        _ => Err(ParseCmdError::InvalidCommand(arguments[1].clone())),
-    }
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub enum GroupBy {
+    #[default]
+    Project,
+    Tag,
+    Ticket,
 }
 
+/// The error of the library, its message is meant for the user.
 #[derive(Debug)]
 pub enum CommandExecutionError {
     CheckedInTwice,
@@ -261,7 +54,21 @@ pub enum CommandExecutionError {
     UnexpectedError(String),
     NoTrackingBeforeCheckIn,
     NoTrackingAfterCurrentTime,
-    ProjectNotConfigured(String),
+    StartAfterEnd,
+    OverlapsChunk(String),
+    ProjectNotConfigured {
+        project: String,
+        suggestion: Option<String>,
+    },
+    ChunkRunning(String),
+    NoChunkRunning,
+    DayMarkedAbsent(NaiveDate),
+    DayAlreadyTracked(NaiveDate),
+    NotConfigured(String),
+    InvalidTicketPattern(String),
+    InvalidHistoryRule(String),
+    NonexistentLocalTime(NaiveDateTime),
+    IntervalTooShort,
 }
 
 impl From<std::io::Error> for CommandExecutionError {
@@ -270,6 +77,110 @@ impl From<std::io::Error> for CommandExecutionError {
     }
 }
 
+impl std::fmt::Display for CommandExecutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandExecutionError::CheckedInTwice => {
+                write!(
+                    f,
+                    "You have already checked in today, no need to check in again!"
+                )
+            }
+            CommandExecutionError::IoError(e) => write!(f, "IO Error: {e}"),
+            CommandExecutionError::NotCheckedIn(date) => {
+                write!(f, "No check-in on date: {}!", date.format("%Y-%m-%d"))
+            }
+            CommandExecutionError::InvalidJsonFormat(e) => write!(f, "Invalid JSON format: {e}"),
+            CommandExecutionError::UnexpectedError(e) => write!(f, "Unexpected error: {e}"),
+            CommandExecutionError::NoTrackingBeforeCheckIn => {
+                write!(f, "You cannot retro-track time before your check-in!")
+            }
+            CommandExecutionError::NoTrackingAfterCurrentTime => {
+                write!(f, "You cannot retro-track time after the current time!")
+            }
+            CommandExecutionError::StartAfterEnd => {
+                write!(f, "The start of a chunk must not be after its end!")
+            }
+            CommandExecutionError::OverlapsChunk(project) => write!(
+                f,
+                "The chunk overlaps time already tracked for '{project}'!"
+            ),
+            CommandExecutionError::ProjectNotConfigured {
+                project,
+                suggestion,
+            } => {
+                write!(
+                    f,
+                    "You are not allowed to track time for the project '{project}' since it is not configured."
+                )?;
+                match suggestion {
+                    Some(suggestion) => write!(f, " Did you mean '{suggestion}'?"),
+                    None => Ok(()),
+                }
+            }
+            CommandExecutionError::ChunkRunning(p) => write!(
+                f,
+                "A chunk for '{p}' is currently running, stop it first: chron stop"
+            ),
+            CommandExecutionError::NoChunkRunning => {
+                write!(f, "There is no running chunk to stop!")
+            }
+            CommandExecutionError::DayMarkedAbsent(date) => write!(
+                f,
+                "The day {} is marked as absent, no time can be tracked!",
+                date.format("%Y-%m-%d")
+            ),
+            CommandExecutionError::DayAlreadyTracked(date) => write!(
+                f,
+                "There is already tracked time on {}, it cannot be marked as absent!",
+                date.format("%Y-%m-%d")
+            ),
+            CommandExecutionError::NotConfigured(setting) => write!(
+                f,
+                "The setting '{setting}' must be configured for this command!"
+            ),
+            CommandExecutionError::InvalidTicketPattern(e) => {
+                write!(f, "A ticket pattern in the config file is not valid: {e}")
+            }
+            CommandExecutionError::InvalidHistoryRule(e) => {
+                write!(f, "A history rule in the config file is not valid: {e}")
+            }
+            CommandExecutionError::IntervalTooShort => {
+                write!(
+                    f,
+                    "The intervals of a pomodoro must be at least a minute long!"
+                )
+            }
+            CommandExecutionError::NonexistentLocalTime(time) => write!(
+                f,
+                "The time {} does not exist in the local time zone!",
+                time.format("%Y-%m-%d %H:%M")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CommandExecutionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommandExecutionError::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<regex::Error> for CommandExecutionError {
+    fn from(error: regex::Error) -> Self {
+        CommandExecutionError::InvalidTicketPattern(error.to_string())
+    }
+}
+
+impl From<rustyline::error::ReadlineError> for CommandExecutionError {
+    fn from(error: rustyline::error::ReadlineError) -> Self {
+        CommandExecutionError::UnexpectedError(error.to_string())
+    }
+}
+
 impl From<serde_json::Error> for CommandExecutionError {
     fn from(error: serde_json::Error) -> Self {
         CommandExecutionError::InvalidJsonFormat(error.to_string())
@@ -284,63 +195,497 @@ impl From<file_handling::DirsError<'_>> for CommandExecutionError {
     }
 }
 
-fn check_in() -> Result<(), CommandExecutionError> {
-    let now = chrono::offset::Local::now();
-
+/// Checks in at the given time, creating the file for its date.
+///
+/// # Errors
+///
+/// Fails if the date was already checked in or is marked as absent, or if the file cannot be
+/// written.
+pub fn check_in(now: NaiveDateTime) -> Result<(), CommandExecutionError> {
     let today = Day {
-        date: now.date_naive(),
+        date: now.date(),
         check_in_time: now.time(),
         chunks: vec![],
+        running: None,
+        absence: None,
     };
 
     let serialized = serde_json::to_string(&today)?;
 
-    let file_path = file_handling::get_today_file_path()?;
+    let file_path = file_handling::get_file_path_for_date(today.date)?;
     file_handling::create_dir_if_not_exists(&file_path).map_err(CommandExecutionError::from)?;
 
     if file_path.exists() {
+        if load_day(today.date)?.absence.is_some() {
+            return Err(CommandExecutionError::DayMarkedAbsent(today.date));
+        }
         return Err(CommandExecutionError::CheckedInTwice);
     }
 
     fs::write(file_path, serialized).map_err(CommandExecutionError::from)
 }
 
-fn track(
+/// Marks a day without work, e.g. because of illness or vacation.
+///
+/// # Errors
+///
+/// Fails if time was already tracked on the day, or if the file cannot be written.
+pub fn absent(date: NaiveDate, reason: Option<String>) -> Result<(), CommandExecutionError> {
+    let file_path = file_handling::get_file_path_for_date(date)?;
+    file_handling::create_dir_if_not_exists(&file_path).map_err(CommandExecutionError::from)?;
+
+    if file_path.exists() {
+        return Err(CommandExecutionError::DayAlreadyTracked(date));
+    }
+
+    let day = Day {
+        date,
+        check_in_time: NaiveTime::MIN,
+        chunks: vec![],
+        running: None,
+        absence: Some(reason.unwrap_or("absent".to_string())),
+    };
+
+    save_day(&day)
+}
+
+/// Tracks a chunk of the day of `now`, ending at the given time.
+///
+/// Returns the rules the day violates with the new chunk, e.g. a missing break.
+///
+/// # Errors
+///
+/// Fails if the project is not configured, a chunk is running, the chunk is not between
+/// the check-in and now, or if the day cannot be loaded or saved.
+pub fn track(
+    now: NaiveDateTime,
+    start_time: Option<NaiveTime>,
     time: NaiveTime,
     project: String,
     description: Option<String>,
-) -> Result<(), CommandExecutionError> {
-    let now = chrono::offset::Local::now();
-    let date = now.date_naive();
-
-    let mut day: Day = load_day(date)?;
+    tags: Vec<String>,
+) -> Result<Vec<Violation>, CommandExecutionError> {
+    let mut day: Day = load_tracking_day(now.date())?;
 
     let config = config::load_config()?;
-    if !config.projects.contains(&project) && project != "break" {
-        return Err(CommandExecutionError::ProjectNotConfigured(project));
-    }
+    check_project_configured(&config, &project)?;
 
-    if time < day.check_in_time {
-        return Err(CommandExecutionError::NoTrackingBeforeCheckIn);
+    if let Some(running) = &day.running {
+        return Err(CommandExecutionError::ChunkRunning(running.project.clone()));
     }
-    if time > now.time() {
-        return Err(CommandExecutionError::NoTrackingAfterCurrentTime);
+
+    check_chunk_times(&day, start_time, time, now.time())?;
+    if let Some(project) = overlapped_project(&day, start_time, time) {
+        return Err(CommandExecutionError::OverlapsChunk(project));
     }
 
     let chunk = Chunk {
+        start_time,
         end_time: time,
+        tags: collect_tags(description.as_deref(), tags),
+        tickets: extract_tickets(
+            description.as_deref(),
+            &config.ticket_patterns_for(&project),
+        )?,
         project,
         description,
     };
 
     day.chunks.push(chunk);
 
-    let serialized = serde_json::to_string(&day)?;
+    save_day(&day)?;
+
+    Ok(check_day(&day, &config.rules))
+}
+
+/// Replaces the chunk at the index with the edited one, checked like a tracked chunk.
+///
+/// The tags are collected from the new description and the tags of the edited chunk, and
+/// the tags given explicitly for the replaced chunk are kept. The tickets are extracted from
+/// the new description. If the end moves, the following chunk keeps its start.
+/// Expects the chunks to be sorted by end time.
+///
+/// # Errors
+///
+/// Fails if there is no chunk at the index, the project is not configured, the chunk is not
+/// between the check-in and now, overlaps another or the running chunk, or a ticket pattern
+/// is not valid.
+pub fn edit_chunk(
+    day: &mut Day,
+    index: usize,
+    edited: Chunk,
+    now: NaiveTime,
+    config: &Config,
+) -> Result<(), CommandExecutionError> {
+    check_project_configured(config, &edited.project)?;
+    check_chunk_times(day, edited.start_time, edited.end_time, now)?;
+
+    if index >= day.chunks.len() {
+        return Err(CommandExecutionError::UnexpectedError(
+            "The chunk does not exist anymore.".to_string(),
+        ));
+    }
+
+    let mut others = day.clone();
+    delete_chunk(&mut others, index);
+    if let Some(project) = overlapped_project(&others, edited.start_time, edited.end_time) {
+        return Err(CommandExecutionError::OverlapsChunk(project));
+    }
+    if let Some(running) = day
+        .running
+        .as_ref()
+        .filter(|running| edited.end_time > running.start_time)
+    {
+        return Err(CommandExecutionError::OverlapsChunk(
+            running.project.clone(),
+        ));
+    }
+
+    let tickets = extract_tickets(
+        edited.description.as_deref(),
+        &config.ticket_patterns_for(&edited.project),
+    )?;
+
+    if day.chunks[index].end_time != edited.end_time {
+        keep_start_of_next(day, index);
+    }
+
+    let chunk = &mut day.chunks[index];
+    // inline tags of the old description are dropped with it
+    let mut tags = explicit_tags(
+        chunk.description.as_deref(),
+        std::mem::take(&mut chunk.tags),
+    );
+    tags.extend(edited.tags);
+
+    *chunk = Chunk {
+        tags: collect_tags(edited.description.as_deref(), tags),
+        tickets,
+        ..edited
+    };
+
+    Ok(())
+}
+
+/// Removes the chunk at the index, its time becomes untracked.
+///
+/// Expects the chunks to be sorted by end time.
+pub fn delete_chunk(day: &mut Day, index: usize) {
+    if index < day.chunks.len() {
+        keep_start_of_next(day, index);
+        day.chunks.remove(index);
+    }
+}
+
+/// Makes the start of the chunk following the one at the index explicit, so that it does
+/// not change with the end of that chunk.
+fn keep_start_of_next(day: &mut Day, index: usize) {
+    let Some(end_time) = day.chunks.get(index).map(|chunk| chunk.end_time) else {
+        return;
+    };
+    if let Some(next) = day.chunks.get_mut(index + 1) {
+        next.start_time.get_or_insert(end_time);
+    }
+}
+
+/// Checks that a chunk between the times lies between the check-in and now.
+fn check_chunk_times(
+    day: &Day,
+    start_time: Option<NaiveTime>,
+    end_time: NaiveTime,
+    now: NaiveTime,
+) -> Result<(), CommandExecutionError> {
+    if start_time.unwrap_or(end_time) < day.check_in_time {
+        return Err(CommandExecutionError::NoTrackingBeforeCheckIn);
+    }
+    if end_time > now {
+        return Err(CommandExecutionError::NoTrackingAfterCurrentTime);
+    }
+    if start_time.is_some_and(|start| start > end_time) {
+        return Err(CommandExecutionError::StartAfterEnd);
+    }
+
+    Ok(())
+}
+
+/// Returns the project of a tracked chunk the new chunk would overlap, if any.
+///
+/// Without a start time the new chunk starts where the chunk ending before it ended.
+fn overlapped_project(
+    day: &Day,
+    start_time: Option<NaiveTime>,
+    end_time: NaiveTime,
+) -> Option<String> {
+    let mut day = day.clone();
+    day.chunks.sort_by_key(|chunk| chunk.end_time);
+
+    let start = start_time.unwrap_or_else(|| {
+        day.chunks
+            .iter()
+            .map(|chunk| chunk.end_time)
+            .filter(|end| *end <= end_time)
+            .max()
+            .unwrap_or(day.check_in_time)
+    });
+
+    day_spans(&day)
+        .iter()
+        .find(|span| span.project != UNTRACKED && span.start < end_time && start < span.end)
+        .map(|span| span.project.to_string())
+}
+
+/// Tracks chunks of the day of `now` one after the other, each ending its duration after
+/// the end of the previous one.
+///
+/// Returns the rules the day violates with the new chunks.
+///
+/// # Errors
+///
+/// Fails like [`track`], without tracking any chunk if one of the projects is not
+/// configured or the last chunk would end after now.
+pub fn track_durations(
+    now: NaiveDateTime,
+    chunks: Vec<DurationChunk>,
+) -> Result<Vec<Violation>, CommandExecutionError> {
+    let day = load_tracking_day(now.date())?;
+
+    let config = config::load_config()?;
+    for chunk in &chunks {
+        check_project_configured(&config, &chunk.project)?;
+    }
+
+    let durations: Vec<Duration> = chunks.iter().map(|chunk| chunk.duration).collect();
+    let end_times = chained_end_times(last_chunk_end(&day), &durations, now.time())?;
+
+    let mut warnings = vec![];
+    for (chunk, end_time) in chunks.into_iter().zip(end_times) {
+        warnings = track(
+            now,
+            None,
+            end_time,
+            chunk.project,
+            chunk.description,
+            chunk.tags,
+        )?;
+    }
+
+    Ok(warnings)
+}
+
+/// Returns the end times of chunks with the given durations, the first one starting at
+/// `start`, as long as the last one does not end after `now`.
+fn chained_end_times(
+    start: NaiveTime,
+    durations: &[Duration],
+    now: NaiveTime,
+) -> Result<Vec<NaiveTime>, CommandExecutionError> {
+    let total = durations
+        .iter()
+        .try_fold(Duration::zero(), |total, duration| {
+            total.checked_add(duration)
+        });
+    if total.is_none_or(|total| total > now - start) {
+        return Err(CommandExecutionError::NoTrackingAfterCurrentTime);
+    }
+
+    let mut end_time = start;
+    Ok(durations
+        .iter()
+        .map(|duration| {
+            end_time += *duration;
+            end_time
+        })
+        .collect())
+}
+
+/// Adds chunks with explicit times to the day and saves it, keeping the chunks sorted
+/// by end time. The tags and tickets are taken from their descriptions.
+///
+/// Returns the rules the day violates with the new chunks.
+///
+/// Expects the chunks to fit into untracked gaps of the day.
+///
+/// # Errors
+///
+/// Fails if a ticket pattern of a project is not valid or if the day cannot be saved.
+pub fn track_chunks(
+    day: &mut Day,
+    chunks: Vec<Chunk>,
+    config: &Config,
+) -> Result<Vec<Violation>, CommandExecutionError> {
+    let chunks = chunks
+        .into_iter()
+        .map(|chunk| {
+            Ok(Chunk {
+                tags: collect_tags(chunk.description.as_deref(), chunk.tags),
+                tickets: extract_tickets(
+                    chunk.description.as_deref(),
+                    &config.ticket_patterns_for(&chunk.project),
+                )?,
+                ..chunk
+            })
+        })
+        .collect::<Result<Vec<_>, CommandExecutionError>>()?;
+
+    insert_chunks(day, chunks);
+    save_day(day)?;
+
+    Ok(check_day(day, &config.rules))
+}
+
+/// Adds the chunks to the day, keeping the chunks sorted by end time.
+fn insert_chunks(day: &mut Day, chunks: Vec<Chunk>) {
+    day.chunks.extend(chunks);
+    day.chunks.sort_by_key(|chunk| chunk.end_time);
+}
+
+/// Checks that chunks can be tracked for the project, which is configured or a break.
+///
+/// # Errors
+///
+/// Fails with [`CommandExecutionError::ProjectNotConfigured`] otherwise, suggesting a
+/// configured project with a similar name.
+pub fn check_project_configured(
+    config: &Config,
+    project: &str,
+) -> Result<(), CommandExecutionError> {
+    if config.has_project(project) || project == "break" {
+        Ok(())
+    } else {
+        Err(CommandExecutionError::ProjectNotConfigured {
+            project: project.to_string(),
+            suggestion: config.similar_project(project).map(str::to_string),
+        })
+    }
+}
+
+/// Tracks a break until now, or switches to a running break if a chunk is running.
+///
+/// Returns the rules the day violates with the break.
+///
+/// # Errors
+///
+/// Fails like [`track`] and [`start`].
+pub fn take_break(
+    now: NaiveDateTime,
+    description: Option<String>,
+) -> Result<Vec<Violation>, CommandExecutionError> {
+    if load_tracking_day(now.date())?.running.is_some() {
+        start(now, "break".to_string(), description, vec![]).map(|()| vec![])
+    } else {
+        track(
+            now,
+            None,
+            now.time(),
+            "break".to_string(),
+            description,
+            vec![],
+        )
+    }
+}
+
+/// Starts a running chunk at `now`, closing the currently running one.
+///
+/// # Errors
+///
+/// Fails if the project is not configured, a ticket pattern is not valid, or if the day
+/// cannot be loaded or saved.
+pub fn start(
+    now: NaiveDateTime,
+    project: String,
+    description: Option<String>,
+    tags: Vec<String>,
+) -> Result<(), CommandExecutionError> {
+    let mut day = load_tracking_day(now.date())?;
+
+    let config = config::load_config()?;
+    check_project_configured(&config, &project)?;
+
+    let tickets = extract_tickets(
+        description.as_deref(),
+        &config.ticket_patterns_for(&project),
+    )?;
+
+    close_running_chunk(&mut day, now.time());
+    day.running = Some(RunningChunk {
+        project,
+        tags: collect_tags(description.as_deref(), tags),
+        tickets,
+        description,
+        start_time: now.time(),
+    });
+
+    save_day(&day)
+}
+
+/// Closes the running chunk at `now`.
+///
+/// # Errors
+///
+/// Fails if no chunk is running, or if the day cannot be loaded or saved.
+pub fn stop(now: NaiveDateTime) -> Result<(), CommandExecutionError> {
+    let mut day = load_tracking_day(now.date())?;
+
+    if !close_running_chunk(&mut day, now.time()) {
+        return Err(CommandExecutionError::NoChunkRunning);
+    }
+
+    save_day(&day)
+}
+
+/// Turns the running chunk into a regular chunk ending at the given time.
+///
+/// Returns whether a chunk was running.
+fn close_running_chunk(day: &mut Day, end_time: NaiveTime) -> bool {
+    match day.running.take() {
+        Some(running) => {
+            day.chunks.push(Chunk {
+                project: running.project,
+                description: running.description,
+                tags: running.tags,
+                tickets: running.tickets,
+                start_time: Some(running.start_time),
+                end_time,
+            });
+            true
+        }
+        None => false,
+    }
+}
+
+/// Writes the day to its file in the data directory.
+///
+/// # Errors
+///
+/// Fails if the data directory is unknown or the file cannot be written.
+pub fn save_day(day: &Day) -> Result<(), CommandExecutionError> {
+    let serialized = serde_json::to_string(day)?;
 
     let file_path = file_handling::get_file_path_for_date(day.date)?;
     fs::write(file_path, serialized).map_err(CommandExecutionError::from)
 }
 
+/// Loads a day to track chunks for, which must not be marked as absent.
+///
+/// # Errors
+///
+/// Fails like [`load_day`], or if the day is marked as absent.
+pub fn load_tracking_day(date: NaiveDate) -> Result<Day, CommandExecutionError> {
+    let day = load_day(date)?;
+
+    if day.absence.is_some() {
+        return Err(CommandExecutionError::DayMarkedAbsent(date));
+    }
+
+    Ok(day)
+}
+
+/// Reads the day from its file in the data directory.
+///
+/// # Errors
+///
+/// Fails with [`CommandExecutionError::NotCheckedIn`] if there is no file for the date,
+/// or if the file cannot be read or parsed.
 pub fn load_day(date: NaiveDate) -> Result<Day, CommandExecutionError> {
     let file_path = file_handling::get_file_path_for_date(date)
         .map_err(CommandExecutionError::from)
@@ -355,12 +700,17 @@ pub fn load_day(date: NaiveDate) -> Result<Day, CommandExecutionError> {
     Ok(serde_json::from_str(&fs::read_to_string(file_path?)?)?)
 }
 
-fn reset() -> Result<(), CommandExecutionError> {
-    let file_path = file_handling::get_today_file_path()?;
+/// Deletes the day with all its chunks.
+///
+/// # Errors
+///
+/// Fails if the file of the day cannot be deleted.
+pub fn reset(date: NaiveDate) -> Result<(), CommandExecutionError> {
+    let file_path = file_handling::get_file_path_for_date(date)?;
     fs::remove_file(file_path).map_err(CommandExecutionError::from)
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Day {
     #[serde(with = "date_format")]
@@ -370,25 +720,67 @@ pub struct Day {
     pub check_in_time: NaiveTime,
 
     pub chunks: Vec<Chunk>,
+
+    /// A chunk started with `chron start` that has not been closed yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub running: Option<RunningChunk>,
+
+    /// Reason why there was no work on this day, e.g. illness or vacation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub absence: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningChunk {
+    pub project: String,
+
+    pub description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Ticket references found in the description.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tickets: Vec<String>,
+
+    #[serde(with = "time_format")]
+    pub start_time: NaiveTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Chunk {
     pub project: String,
 
     pub description: Option<String>,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Ticket references found in the description.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tickets: Vec<String>,
+
+    /// Only set if the chunk does not start where the previous one ended.
+    #[serde(
+        default,
+        with = "optional_time_format",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub start_time: Option<NaiveTime>,
+
     #[serde(with = "time_format")]
     pub end_time: NaiveTime,
 }
 
-mod date_format {
+pub(crate) mod date_format {
     use chrono::NaiveDate;
     use serde::{Deserialize, Deserializer, Serializer};
 
-    const FORMAT: &str = "%Y-%m-%d";
+    pub const FORMAT: &str = "%Y-%m-%d";
 
+    #[allow(clippy::trivially_copy_pass_by_ref)]
     pub fn serialize<S>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -402,16 +794,17 @@ mod date_format {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        Ok(NaiveDate::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)?)
+        NaiveDate::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)
     }
 }
 
-mod time_format {
+pub(crate) mod time_format {
     use chrono::NaiveTime;
     use serde::{Deserialize, Deserializer, Serializer};
 
-    const FORMAT: &str = "%H:%M";
+    pub const FORMAT: &str = "%H:%M";
 
+    #[allow(clippy::trivially_copy_pass_by_ref)]
     pub fn serialize<S>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -425,186 +818,313 @@ mod time_format {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        Ok(NaiveTime::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)?)
+        NaiveTime::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)
     }
 }
 
-#[cfg(test)]
-#[allow(clippy::unwrap_used, clippy::manual_string_new)]
-mod tests {
-    use super::*;
+pub(crate) mod optional_date_format {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Deserializer, Serializer};
 
-    fn to_args(args: &[&str]) -> Vec<String> {
-        args.iter().map(std::string::ToString::to_string).collect()
+    #[allow(clippy::ref_option, clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S>(date: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(date) => super::date_format::serialize(date, serializer),
+            None => serializer.serialize_none(),
+        }
     }
 
-    #[test]
-    fn test_parse_invalid_and_missing_and_version() {
-        let args = to_args(&[""]);
-        assert_eq!(parse_command(args), Err(ParseCmdError::NoCommand));
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| {
+                NaiveDate::parse_from_str(&s, super::date_format::FORMAT)
+                    .map_err(serde::de::Error::custom)
+            })
+            .transpose()
+    }
+}
 
-        let args = to_args(&["", "invalid"]);
-        assert_eq!(
-            parse_command(args),
-            Err(ParseCmdError::InvalidCommand("invalid".to_string()))
-        );
+pub(crate) mod optional_time_format {
+    use chrono::NaiveTime;
+    use serde::{Deserialize, Deserializer, Serializer};
 
-        let args = to_args(&["", "version"]);
-        assert_eq!(parse_command(args), Ok(Command::Version));
+    #[allow(clippy::ref_option)]
+    pub fn serialize<S>(time: &Option<NaiveTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match time {
+            Some(time) => super::time_format::serialize(time, serializer),
+            None => serializer.serialize_none(),
+        }
     }
 
-    #[test]
-    fn test_parse_track() {
-        let args = to_args(&["", "track"]);
-        assert_eq!(
-            parse_command(args),
-            Err(ParseCmdError::MissingParameter(
-                "The command 'track' requires a parameter 'project'".to_string()
-            ))
-        );
-
-        let args = to_args(&["", "track", "project"]);
-        assert_eq!(
-            parse_command(args),
-            Ok(Command::Track {
-                project: "project".to_string(),
-                description: None,
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| {
+                NaiveTime::parse_from_str(&s, super::time_format::FORMAT)
+                    .map_err(serde::de::Error::custom)
             })
-        );
+            .transpose()
+    }
+}
 
-        let args = to_args(&["", "track", "project", "a", "description"]);
-        assert_eq!(
-            parse_command(args),
-            Ok(Command::Track {
-                project: "project".to_string(),
-                description: Some("a description".to_string()),
-            })
-        );
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_util::{chunk, day, time};
 
-        let args = to_args(&["", "break"]);
-        assert_eq!(parse_command(args), Ok(Command::Break(None)));
+    #[test]
+    fn test_chained_end_times() {
+        let time = |hours, minutes| NaiveTime::from_hms_opt(hours, minutes, 0).unwrap();
+        let durations = [Duration::minutes(40), Duration::minutes(20)];
 
-        let args = to_args(&["", "break", "a", "description"]);
         assert_eq!(
-            parse_command(args),
-            Ok(Command::Break(Some("a description".to_string())))
+            chained_end_times(time(9, 0), &durations, time(10, 0)).unwrap(),
+            vec![time(9, 40), time(10, 0)]
         );
+        assert!(matches!(
+            chained_end_times(time(9, 0), &durations, time(9, 59)),
+            Err(CommandExecutionError::NoTrackingAfterCurrentTime)
+        ));
     }
 
     #[test]
-    fn test_parse_projects() {
-        let args = to_args(&["", "projects"]);
-        assert_eq!(
-            parse_command(args),
-            Err(ParseCmdError::MissingParameter(
-                "The command 'projects' requires a subcommand: chron projects <subcommand>"
-                    .to_string()
-            ))
-        );
+    fn test_overlapped_project() {
+        let day = day(vec![
+            Chunk {
+                start_time: Some(time(9, 0)),
+                ..chunk("kyoshi", time(11, 0))
+            },
+            chunk("korra", time(12, 0)),
+        ]);
 
-        let args = to_args(&["", "projects", "add"]);
         assert_eq!(
-            parse_command(args),
-            Err(ParseCmdError::MissingParameter(
-                "The command 'projects add' requires a parameter 'project'".to_string()
-            ))
+            overlapped_project(&day, Some(time(9, 30)), time(10, 0)),
+            Some("kyoshi".to_string())
         );
-
-        let args = to_args(&["", "projects", "add", "project"]);
         assert_eq!(
-            parse_command(args),
-            Ok(Command::Projects(ProjectsSubCommand::Add(
-                "project".to_string()
-            )))
+            overlapped_project(&day, Some(time(10, 30)), time(11, 30)),
+            Some("kyoshi".to_string())
         );
-
-        let args = to_args(&["", "projects", "delete"]);
+        // the gap between check-in and the first chunk is free
+        assert_eq!(overlapped_project(&day, None, time(8, 30)), None);
         assert_eq!(
-            parse_command(args),
-            Err(ParseCmdError::MissingParameter(
-                "The command 'projects delete' requires a parameter 'project'".to_string()
-            ))
+            overlapped_project(&day, Some(time(8, 30)), time(9, 0)),
+            None
         );
-
-        let args = to_args(&["", "projects", "delete", "project"]);
+        // a chunk without a start ending inside a chunk covers its beginning
         assert_eq!(
-            parse_command(args),
-            Ok(Command::Projects(ProjectsSubCommand::Delete(
-                "project".to_string()
-            )))
+            overlapped_project(&day, None, time(11, 30)),
+            Some("korra".to_string())
         );
+        assert_eq!(overlapped_project(&day, None, time(13, 0)), None);
+    }
 
-        let args = to_args(&["", "projects", "invalid"]);
+    #[test]
+    fn test_edit_chunk() {
+        let config = Config {
+            projects: vec![crate::config::Project {
+                ticket_patterns: vec![r"KY-\d+".to_string()],
+                ..crate::config::Project::new("kyoshi".to_string())
+            }],
+            ..Config::default()
+        };
+        let mut day = day(vec![Chunk {
+            description: Some("review #old".to_string()),
+            tags: vec!["old".to_string(), "explicit".to_string()],
+            ..chunk("kyoshi", time(9, 0))
+        }]);
+        let edited = |project: &str, end_time| Chunk {
+            description: Some("fix KY-12 #new".to_string()),
+            tags: vec!["given".to_string()],
+            ..chunk(project, end_time)
+        };
+
+        assert!(matches!(
+            edit_chunk(
+                &mut day,
+                0,
+                edited("korra", time(9, 30)),
+                time(12, 0),
+                &config
+            ),
+            Err(CommandExecutionError::ProjectNotConfigured { .. })
+        ));
+        assert!(matches!(
+            edit_chunk(
+                &mut day,
+                0,
+                edited("kyoshi", time(12, 30)),
+                time(12, 0),
+                &config
+            ),
+            Err(CommandExecutionError::NoTrackingAfterCurrentTime)
+        ));
+
+        edit_chunk(
+            &mut day,
+            0,
+            edited("kyoshi", time(9, 30)),
+            time(12, 0),
+            &config,
+        )
+        .unwrap();
+        let chunk = &day.chunks[0];
+        assert_eq!(chunk.end_time, time(9, 30));
+        assert_eq!(chunk.tickets, vec!["KY-12".to_string()]);
         assert_eq!(
-            parse_command(args),
-            Err(ParseCmdError::InvalidCommand(
-                "projects invalid".to_string()
-            ))
+            chunk.tags,
+            vec![
+                "new".to_string(),
+                "explicit".to_string(),
+                "given".to_string()
+            ]
         );
+    }
 
-        let args = to_args(&["", "projects", "list"]);
+    #[test]
+    fn test_edit_chunk_overlaps() {
+        let config = Config {
+            projects: vec![
+                crate::config::Project::new("kyoshi".to_string()),
+                crate::config::Project::new("korra".to_string()),
+            ],
+            ..Config::default()
+        };
+        let mut day = Day {
+            running: Some(RunningChunk {
+                project: "korra".to_string(),
+                start_time: time(10, 30),
+                ..RunningChunk::default()
+            }),
+            ..day(vec![
+                chunk("kyoshi", time(9, 0)),
+                chunk("korra", time(10, 0)),
+            ])
+        };
+        let edit = |day: &mut Day, index, start_time, end_time| {
+            let edited = Chunk {
+                start_time,
+                ..chunk("kyoshi", end_time)
+            };
+            edit_chunk(day, index, edited, time(12, 0), &config)
+        };
+
+        assert!(matches!(
+            edit(&mut day, 0, None, time(9, 30)),
+            Err(CommandExecutionError::OverlapsChunk(project)) if project == "korra"
+        ));
+        assert!(matches!(
+            edit(&mut day, 1, Some(time(8, 30)), time(10, 0)),
+            Err(CommandExecutionError::OverlapsChunk(project)) if project == "kyoshi"
+        ));
+        assert!(matches!(
+            edit(&mut day, 1, None, time(10, 45)),
+            Err(CommandExecutionError::OverlapsChunk(project)) if project == "korra"
+        ));
+
+        // the next chunk keeps its start when the end moves
+        edit(&mut day, 0, None, time(8, 30)).unwrap();
+        assert_eq!(day.chunks[0].end_time, time(8, 30));
+        assert_eq!(day.chunks[1].start_time, Some(time(9, 0)));
+    }
+
+    #[test]
+    fn test_insert_chunks() {
+        let mut day = day(vec![
+            chunk("kyoshi", time(9, 0)),
+            Chunk {
+                start_time: Some(time(10, 0)),
+                ..chunk("korra", time(11, 0))
+            },
+        ]);
+
+        insert_chunks(
+            &mut day,
+            vec![
+                Chunk {
+                    start_time: Some(time(9, 0)),
+                    ..chunk("kyoshi", time(9, 30))
+                },
+                Chunk {
+                    start_time: Some(time(9, 30)),
+                    ..chunk("break", time(10, 0))
+                },
+            ],
+        );
+        let ends: Vec<_> = day.chunks.iter().map(|chunk| chunk.end_time).collect();
         assert_eq!(
-            parse_command(args),
-            Ok(Command::Projects(ProjectsSubCommand::List))
+            ends,
+            vec![time(9, 0), time(9, 30), time(10, 0), time(11, 0)]
         );
     }
 
     #[test]
-    fn test_parse_check_in_and_reset() {
-        let args = to_args(&["", "check-in"]);
-        assert_eq!(parse_command(args), Ok(Command::CheckIn));
-
-        let args = to_args(&["", "reset"]);
-        assert_eq!(parse_command(args), Ok(Command::Reset));
+    fn test_delete_chunk() {
+        let mut day = day(vec![
+            chunk("kyoshi", time(9, 0)),
+            chunk("korra", time(10, 0)),
+            chunk("kyoshi", time(11, 0)),
+        ]);
+
+        delete_chunk(&mut day, 0);
+        delete_chunk(&mut day, 5);
+
+        assert_eq!(day.chunks.len(), 2);
+        assert_eq!(day.chunks[0].start_time, Some(time(9, 0)));
+        assert_eq!(day.chunks[1].start_time, None);
+        assert_eq!(crate::reporting::work_duration(&day), Duration::hours(2));
     }
 
     #[test]
-    fn test_parse_retrotrack() {
-        let args = to_args(&["", "retrotrack"]);
-        assert_eq!(
-            parse_command(args),
-            Err(ParseCmdError::MissingParameter(
-                "The command 'retrotrack' requires a parameter 'end_time'".to_string()
-            ))
-        );
+    fn test_close_running_chunk() {
+        let mut day = day(vec![]);
 
-        let args = to_args(&["", "retrotrack", "10:00"]);
-        assert_eq!(
-            parse_command(args),
-            Err(ParseCmdError::MissingParameter(
-                "The command 'retrotrack' requires a parameter 'project'".to_string()
-            ))
-        );
+        assert!(!close_running_chunk(&mut day, time(9, 0)));
+        assert!(day.chunks.is_empty());
 
-        let args = to_args(&["", "retrotrack", "10:00", "project"]);
-        assert_eq!(
-            parse_command(args),
-            Ok(Command::RetroTrack {
-                end_time: NaiveTime::parse_from_str("10:00", "%H:%M").unwrap(),
-                project: "project".to_string(),
-                description: None,
-            })
-        );
+        day.running = Some(RunningChunk {
+            project: "kyoshi".to_string(),
+            start_time: time(8, 30),
+            ..RunningChunk::default()
+        });
 
-        let args = to_args(&["", "retrotrack", "10:00", "project", "a", "description"]);
-        assert_eq!(
-            parse_command(args),
-            Ok(Command::RetroTrack {
-                end_time: NaiveTime::parse_from_str("10:00", "%H:%M").unwrap(),
-                project: "project".to_string(),
-                description: Some("a description".to_string()),
-            })
-        );
+        assert!(close_running_chunk(&mut day, time(9, 0)));
+        assert!(day.running.is_none());
+        assert_eq!(day.chunks.len(), 1);
+        assert_eq!(day.chunks[0].start_time, Some(time(8, 30)));
+        assert_eq!(day.chunks[0].end_time, time(9, 0));
     }
 
     #[test]
-    fn test_parse_report() {
-        let args = to_args(&["", "report"]);
+    fn test_chunk_start_time_is_optional() {
+        let chunk: Chunk =
+            serde_json::from_str(r#"{"project":"kyoshi","description":null,"endTime":"10:00"}"#)
+                .unwrap();
+        assert_eq!(chunk.start_time, None);
+        assert_eq!(
+            serde_json::to_string(&chunk).unwrap(),
+            r#"{"project":"kyoshi","description":null,"endTime":"10:00"}"#
+        );
+
+        let chunk: Chunk = serde_json::from_str(
+            r#"{"project":"kyoshi","description":null,"startTime":"09:30","endTime":"10:00"}"#,
+        )
+        .unwrap();
         assert_eq!(
-            parse_command(args),
-            Ok(Command::Report(ReportSubCommand::Day(
-                chrono::offset::Local::now().date_naive()
-            )))
+            chunk.start_time,
+            Some(NaiveTime::from_hms_opt(9, 30, 0).unwrap())
         );
     }
 }
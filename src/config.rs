@@ -1,15 +1,294 @@
 use crate::commands::CommandExecutionError;
 use crate::file_handling;
+use crate::git::GitConfig;
+use crate::history::HistoryConfig;
+use crate::pomodoro::PomodoroConfig;
+use crate::remind::Reminders;
+use crate::rounding::Rounding;
+use crate::rules::{BreakDeduction, Rule};
+use crate::schedule::RecurringChunk;
+use chrono::{Datelike, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Config {
-    pub projects: Vec<String>,
+    #[serde(
+        serialize_with = "serialize_projects",
+        deserialize_with = "deserialize_projects"
+    )]
+    pub projects: Vec<Project>,
     // aliases will be an object. keys will be "new name" and values
     // will be all projects mapped to the alias.
     // aliases: HashMap<String, Vec<String>>,
+    /// Hours of work (without breaks) that are expected from Monday to Friday.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_target_hours: Option<f64>,
+
+    /// Hours of work expected per weekday, takes precedence over `daily_target_hours`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_hours: Option<WeekdayHours>,
+
+    /// First day that is taken into account for the overtime balance.
+    #[serde(
+        default,
+        with = "crate::commands::optional_date_format",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub balance_start_date: Option<NaiveDate>,
+
+    /// Rules about working times, e.g. from labor law, that every day is checked against.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>,
+
+    /// Deducts missing breaks required by the rules from the work in reports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub break_deduction: Option<BreakDeduction>,
+
+    /// Hotkeys of the dashboard that track a project with a description.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quick_track: Vec<QuickTrack>,
+
+    /// When `chron remind` reminds to track or to check in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<Reminders>,
+
+    /// Where `chron suggest --git` looks for commits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitConfig>,
+
+    /// Where `chron suggest --history` reads commands and how they map to projects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<HistoryConfig>,
+
+    /// Lengths of the intervals of `chron pomodoro`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pomodoro: Option<PomodoroConfig>,
+
+    /// Chunks that recur every week, tracked by `chron apply-schedule`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<RecurringChunk>,
+}
+
+/// A favourite project, tracked with a single key in the dashboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickTrack {
+    pub key: char,
+    pub project: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounding: Option<Rounding>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub billable: bool,
+
+    /// Hourly rate for billable time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+
+    /// Regular expressions for ticket references in descriptions, e.g. `ABC-\d+`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ticket_patterns: Vec<String>,
+}
+
+impl Project {
+    #[must_use]
+    pub fn new(name: String) -> Self {
+        Project {
+            name,
+            ..Project::default()
+        }
+    }
+
+    /// Whether the project has nothing configured but its name.
+    fn is_plain(&self) -> bool {
+        self == &Project::new(self.name.clone())
+    }
+}
+
+/// A project in the config file, which is either only a name or a full definition.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ProjectEntry {
+    Name(String),
+    Project(Project),
+}
+
+fn serialize_projects<S>(projects: &[Project], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    projects
+        .iter()
+        .map(|project| {
+            if project.is_plain() {
+                ProjectEntry::Name(project.name.clone())
+            } else {
+                ProjectEntry::Project(project.clone())
+            }
+        })
+        .collect::<Vec<ProjectEntry>>()
+        .serialize(serializer)
+}
+
+fn deserialize_projects<'de, D>(deserializer: D) -> Result<Vec<Project>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Vec::<ProjectEntry>::deserialize(deserializer)?
+        .into_iter()
+        .map(|entry| match entry {
+            ProjectEntry::Name(name) => Project::new(name),
+            ProjectEntry::Project(project) => project,
+        })
+        .collect())
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeekdayHours {
+    pub monday: f64,
+    pub tuesday: f64,
+    pub wednesday: f64,
+    pub thursday: f64,
+    pub friday: f64,
+    pub saturday: f64,
+    pub sunday: f64,
+}
+
+impl Config {
+    #[must_use]
+    pub fn project(&self, name: &str) -> Option<&Project> {
+        self.projects.iter().find(|project| project.name == name)
+    }
+
+    /// Whether the project is configured, either directly or as parent of a sub-project
+    /// like `kyoshi` for `kyoshi/backend`.
+    #[must_use]
+    pub fn has_project(&self, name: &str) -> bool {
+        self.projects.iter().any(|project| {
+            project.name == name
+                || project
+                    .name
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    /// Adds the project unless a project of the same name is configured, even if it is
+    /// already implied as parent of a sub-project. Returns whether it was added.
+    fn insert_project(&mut self, name: String) -> bool {
+        if self.project(&name).is_some() {
+            return false;
+        }
+
+        self.projects.push(Project::new(name));
+        true
+    }
+
+    /// Returns the configured project most similar to the name, if any is similar enough to
+    /// be a typo.
+    #[must_use]
+    pub fn similar_project(&self, name: &str) -> Option<&str> {
+        self.projects
+            .iter()
+            .map(|project| (strsim::jaro(name, &project.name), project.name.as_str()))
+            .filter(|(similarity, _)| *similarity > 0.7)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, project)| project)
+    }
+
+    /// Returns the ticket patterns of the project and of its parents.
+    #[must_use]
+    pub fn ticket_patterns_for(&self, name: &str) -> Vec<String> {
+        self.projects
+            .iter()
+            .filter(|project| {
+                name == project.name
+                    || name
+                        .strip_prefix(&project.name)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .flat_map(|project| project.ticket_patterns.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the rounding of the project, or of its nearest parent with a rounding.
+    #[must_use]
+    pub fn rounding_for(&self, name: &str) -> Option<Rounding> {
+        self.nearest_project(name, |project| project.rounding.is_some())
+            .and_then(|project| project.rounding)
+    }
+
+    /// Returns the project that time tracked on the project is billed to, i.e. the project
+    /// itself or its nearest parent if it is billable.
+    #[must_use]
+    pub fn billing_project(&self, name: &str) -> Option<&Project> {
+        self.nearest_project(name, |project| project.billable)
+    }
+
+    /// Returns the configured project or its nearest parent that matches the predicate.
+    fn nearest_project(
+        &self,
+        name: &str,
+        predicate: impl Fn(&Project) -> bool,
+    ) -> Option<&Project> {
+        let mut name = name;
+        loop {
+            if let Some(project) = self.project(name).filter(|project| predicate(project)) {
+                return Some(project);
+            }
+            name = &name[..name.rfind('/')?];
+        }
+    }
+
+    /// Returns the time that is expected to be worked on the given date, if configured.
+    #[must_use]
+    pub fn target_for(&self, date: NaiveDate) -> Option<chrono::Duration> {
+        let hours = match &self.target_hours {
+            Some(target_hours) => match date.weekday() {
+                Weekday::Mon => target_hours.monday,
+                Weekday::Tue => target_hours.tuesday,
+                Weekday::Wed => target_hours.wednesday,
+                Weekday::Thu => target_hours.thursday,
+                Weekday::Fri => target_hours.friday,
+                Weekday::Sat => target_hours.saturday,
+                Weekday::Sun => target_hours.sunday,
+            },
+            None => match date.weekday() {
+                Weekday::Sat | Weekday::Sun => self.daily_target_hours.map(|_| 0.0)?,
+                _ => self.daily_target_hours?,
+            },
+        };
+
+        Some(hours_to_duration(hours))
+    }
 }
 
+#[allow(clippy::cast_possible_truncation)]
+#[must_use]
+pub fn hours_to_duration(hours: f64) -> chrono::Duration {
+    chrono::Duration::minutes((hours * 60.0).round() as i64)
+}
+
+/// Reads the config file, creating an empty one if there is none.
+///
+/// # Errors
+///
+/// Fails if the config file cannot be read, written or parsed.
 pub fn load_config() -> Result<Config, CommandExecutionError> {
     let config_file_path = file_handling::get_config_file_path()?;
     if !config_file_path.exists() {
@@ -22,30 +301,175 @@ pub fn load_config() -> Result<Config, CommandExecutionError> {
 }
 
 fn save_initial_config() -> Result<(), CommandExecutionError> {
-    let config = Config { projects: vec![] };
-    save_config(config)
+    save_config(&Config::default())
 }
 
-fn save_config(config: Config) -> Result<(), CommandExecutionError> {
+/// Writes the config file.
+///
+/// # Errors
+///
+/// Fails if the config directory is unknown or the file cannot be written.
+pub fn save_config(config: &Config) -> Result<(), CommandExecutionError> {
     let config_file_path = file_handling::get_config_file_path()?;
     file_handling::create_dir_if_not_exists(&config_file_path)?;
-    std::fs::write(config_file_path, serde_json::to_string(&config)?)
+    std::fs::write(config_file_path, serde_json::to_string(config)?)
         .map_err(CommandExecutionError::from)
 }
 
+/// Adds a project to the config file.
+///
+/// # Errors
+///
+/// Fails if the config file cannot be loaded or saved.
 pub fn add_project(project: String) -> Result<(), CommandExecutionError> {
     let mut config = load_config()?;
 
-    if config.projects.iter().any(|p| p == &project) {
-        Ok(())
+    if config.insert_project(project) {
+        save_config(&config)
     } else {
-        config.projects.push(project);
-        save_config(config)
+        Ok(())
     }
 }
 
-pub fn delete_project(project: String) -> Result<(), CommandExecutionError> {
+/// Removes a project from the config file.
+///
+/// # Errors
+///
+/// Fails if the config file cannot be loaded or saved.
+pub fn delete_project(project: &str) -> Result<(), CommandExecutionError> {
     let mut config = load_config()?;
-    config.projects.retain(|p| p != &project);
-    save_config(config)
+    config.projects.retain(|p| p.name != project);
+    save_config(&config)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::rounding::{RoundingMode, RoundingScope};
+
+    #[test]
+    fn test_projects_from_json() {
+        let config: Config = serde_json::from_str(
+            r#"{"projects": ["korra", {"name": "kyoshi", "rounding": {"minutes": 15}}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.projects[0], Project::new("korra".to_string()));
+        assert_eq!(
+            config.rounding_for("kyoshi"),
+            Some(Rounding {
+                minutes: 15,
+                mode: RoundingMode::Up,
+                scope: RoundingScope::Chunk,
+            })
+        );
+
+        assert_eq!(
+            serde_json::to_string(&config).unwrap(),
+            r#"{"projects":["korra",{"name":"kyoshi","rounding":{"minutes":15,"mode":"up","scope":"chunk"}}]}"#
+        );
+    }
+
+    #[test]
+    fn test_has_project() {
+        let config = Config {
+            projects: vec![
+                Project::new("korra".to_string()),
+                Project::new("kyoshi/frontend/design".to_string()),
+            ],
+            ..Config::default()
+        };
+
+        assert!(config.has_project("korra"));
+        assert!(config.has_project("kyoshi"));
+        assert!(config.has_project("kyoshi/frontend"));
+        assert!(config.has_project("kyoshi/frontend/design"));
+        assert!(!config.has_project("kyo"));
+        assert!(!config.has_project("kyoshi/backend"));
+        assert!(!config.has_project("korra/backend"));
+    }
+
+    #[test]
+    fn test_insert_project() {
+        let mut config = Config {
+            projects: vec![Project::new("kyoshi/backend".to_string())],
+            ..Config::default()
+        };
+
+        assert!(config.insert_project("kyoshi".to_string()));
+        assert!(!config.insert_project("kyoshi".to_string()));
+        assert!(!config.insert_project("kyoshi/backend".to_string()));
+
+        let names: Vec<_> = config.projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["kyoshi/backend", "kyoshi"]);
+    }
+
+    #[test]
+    fn test_similar_project() {
+        let config = Config {
+            projects: vec![
+                Project::new("korra".to_string()),
+                Project::new("kyoshi".to_string()),
+            ],
+            ..Config::default()
+        };
+
+        assert_eq!(config.similar_project("kyohsi"), Some("kyoshi"));
+        assert_eq!(config.similar_project("kora"), Some("korra"));
+        assert_eq!(config.similar_project("aang"), None);
+    }
+
+    #[test]
+    fn test_ticket_patterns_for() {
+        let config = Config {
+            projects: vec![
+                Project {
+                    ticket_patterns: vec![r"ABC-\d+".to_string()],
+                    ..Project::new("kyoshi".to_string())
+                },
+                Project {
+                    ticket_patterns: vec![r"#\d+".to_string()],
+                    ..Project::new("kyoshi/backend".to_string())
+                },
+            ],
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.ticket_patterns_for("kyoshi/backend"),
+            vec![r"ABC-\d+".to_string(), r"#\d+".to_string()]
+        );
+        assert_eq!(
+            config.ticket_patterns_for("kyoshi"),
+            vec![r"ABC-\d+".to_string()]
+        );
+        assert!(config.ticket_patterns_for("kyoshiro").is_empty());
+    }
+
+    #[test]
+    fn test_target_for() {
+        let monday = NaiveDate::from_ymd_opt(2023, 11, 13).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2023, 11, 17).unwrap();
+        let saturday = NaiveDate::from_ymd_opt(2023, 11, 18).unwrap();
+
+        let mut config = Config::default();
+        assert_eq!(config.target_for(monday), None);
+
+        config.daily_target_hours = Some(8.0);
+        assert_eq!(config.target_for(monday), Some(chrono::Duration::hours(8)));
+        assert_eq!(config.target_for(saturday), Some(chrono::Duration::zero()));
+
+        config.target_hours = Some(WeekdayHours {
+            monday: 8.5,
+            friday: 6.0,
+            ..WeekdayHours::default()
+        });
+        assert_eq!(
+            config.target_for(monday),
+            Some(chrono::Duration::minutes(510))
+        );
+        assert_eq!(config.target_for(friday), Some(chrono::Duration::hours(6)));
+        assert_eq!(config.target_for(saturday), Some(chrono::Duration::zero()));
+    }
 }
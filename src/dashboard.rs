@@ -0,0 +1,697 @@
+use chron::{
+    check_day, check_in, compute_status, counted_work_duration, day_spans, delete_chunk,
+    edit_chunk, format_hours_minutes, format_short_status, get_days_of_week, load_available_days,
+    load_config, load_day, parse_time_input, project_summary, save_day, take_break, track, Chunk,
+    Config, Day, Error, QuickTrack, ReportOptions, UNTRACKED,
+};
+use chrono::{NaiveDateTime, NaiveTime};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+
+/// How often the dashboard is refreshed without a key being pressed.
+const TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+const FIELDS: [&str; 4] = ["project", "description", "start", "end"];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum View {
+    Today,
+    Week,
+}
+
+/// A chunk being edited, with its fields as typed.
+#[derive(Debug, PartialEq)]
+struct ChunkEditor {
+    index: usize,
+    /// Start of the chunk, also if it starts where the previous one ended.
+    span_start: NaiveTime,
+    fields: [String; 4],
+    focus: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum Mode {
+    Browse,
+    Edit(ChunkEditor),
+    ConfirmDelete,
+}
+
+/// What the dashboard has to do after a key was pressed.
+enum Action {
+    Quit,
+    CheckIn,
+    Break,
+    Track(QuickTrack),
+    Save(Day),
+}
+
+struct App<'a> {
+    config: &'a Config,
+    view: View,
+    mode: Mode,
+    /// Today, if checked in.
+    day: Option<Day>,
+    selected: usize,
+    message: String,
+    now: NaiveDateTime,
+}
+
+/// Runs the full-screen dashboard until the user quits.
+///
+/// # Errors
+///
+/// Fails if the config cannot be loaded or the terminal cannot be drawn.
+pub fn dashboard() -> Result<(), Error> {
+    let config = load_config()?;
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &config);
+    ratatui::restore();
+
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal, config: &Config) -> Result<(), Error> {
+    let mut app = App::new(config, chrono::offset::Local::now().naive_local());
+
+    loop {
+        app.now = chrono::offset::Local::now().naive_local();
+        if !matches!(app.mode, Mode::Edit(_)) {
+            app.reload();
+        }
+
+        terminal.draw(|frame| app.render(frame))?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let Some(action) = app.handle_key(key.code) else {
+            continue;
+        };
+        let result = match action {
+            Action::Quit => return Ok(()),
+            Action::CheckIn => check_in(app.now).map(|()| ("Checked in".to_string(), vec![])),
+            Action::Break => {
+                take_break(app.now, None).map(|warnings| ("Took a break".to_string(), warnings))
+            }
+            Action::Track(quick_track) => {
+                let project = quick_track.project.clone();
+                track(
+                    app.now,
+                    None,
+                    app.now.time(),
+                    quick_track.project,
+                    quick_track.description,
+                    vec![],
+                )
+                .map(|warnings| (format!("Tracked {project}"), warnings))
+            }
+            Action::Save(day) => save_day(&day).map(|()| {
+                (
+                    "Saved the chunk".to_string(),
+                    check_day(&day, &config.rules),
+                )
+            }),
+        };
+
+        app.reload();
+        app.message = match result {
+            Ok((message, warnings)) => warnings
+                .first()
+                .map_or(message, |violation| format!("WARNING: {violation}")),
+            Err(e) => e.to_string(),
+        };
+    }
+}
+
+/// Returns the configured hotkeys, or the digits 1 to 9 for the first projects.
+fn hotkeys(config: &Config) -> Vec<QuickTrack> {
+    if !config.quick_track.is_empty() {
+        return config.quick_track.clone();
+    }
+
+    config
+        .projects
+        .iter()
+        .zip('1'..='9')
+        .map(|(project, key)| QuickTrack {
+            key,
+            project: project.name.clone(),
+            description: None,
+        })
+        .collect()
+}
+
+impl<'a> App<'a> {
+    fn new(config: &'a Config, now: NaiveDateTime) -> Self {
+        App {
+            config,
+            view: View::Today,
+            mode: Mode::Browse,
+            day: None,
+            selected: 0,
+            message: String::new(),
+            now,
+        }
+    }
+
+    /// Loads today from its file, e.g. after it was changed by another command.
+    fn reload(&mut self) {
+        self.day = load_day(self.now.date())
+            .ok()
+            .filter(|day| day.absence.is_none())
+            .map(|mut day| {
+                day.chunks.sort_by_key(|chunk| chunk.end_time);
+                day
+            });
+
+        let chunks = self.day.as_ref().map_or(0, |day| day.chunks.len());
+        self.selected = self.selected.min(chunks.saturating_sub(1));
+    }
+
+    /// Returns the chunks of today with their start.
+    fn chunk_rows(&self) -> Vec<(NaiveTime, NaiveTime, &str, &str)> {
+        self.day.as_ref().map_or_else(Vec::new, |day| {
+            day_spans(day)
+                .into_iter()
+                .filter(|span| span.project != UNTRACKED)
+                .map(|span| {
+                    (
+                        span.start,
+                        span.end,
+                        span.project,
+                        span.description.map_or("", String::as_str),
+                    )
+                })
+                .collect()
+        })
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Option<Action> {
+        match std::mem::replace(&mut self.mode, Mode::Browse) {
+            Mode::Browse => self.handle_browse_key(key),
+            Mode::ConfirmDelete => {
+                self.message.clear();
+                if key != KeyCode::Char('y') {
+                    return None;
+                }
+                let mut day = self.day.clone()?;
+                delete_chunk(&mut day, self.selected);
+                Some(Action::Save(day))
+            }
+            Mode::Edit(mut editor) => {
+                match key {
+                    KeyCode::Esc => return None,
+                    KeyCode::Enter => match self.apply_edit(&editor) {
+                        Ok(day) => return Some(Action::Save(day)),
+                        Err(message) => self.message = message,
+                    },
+                    KeyCode::Tab | KeyCode::Down => {
+                        editor.focus = (editor.focus + 1) % FIELDS.len();
+                    }
+                    KeyCode::BackTab | KeyCode::Up => {
+                        editor.focus = (editor.focus + FIELDS.len() - 1) % FIELDS.len();
+                    }
+                    KeyCode::Backspace => {
+                        editor.fields[editor.focus].pop();
+                    }
+                    KeyCode::Char(c) => editor.fields[editor.focus].push(c),
+                    _ => (),
+                }
+                self.mode = Mode::Edit(editor);
+                None
+            }
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyCode) -> Option<Action> {
+        let chunks = self.chunk_rows().len();
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Tab => {
+                self.view = match self.view {
+                    View::Today => View::Week,
+                    View::Week => View::Today,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(chunks.saturating_sub(1));
+            }
+            KeyCode::Enter | KeyCode::Char('e') if self.selected < chunks => {
+                let (start, end, project, description) = self.chunk_rows()[self.selected];
+                let explicit_start = self
+                    .day
+                    .as_ref()
+                    .and_then(|day| day.chunks[self.selected].start_time);
+                self.mode = Mode::Edit(ChunkEditor {
+                    index: self.selected,
+                    span_start: start,
+                    fields: [
+                        project.to_string(),
+                        description.to_string(),
+                        explicit_start
+                            .map_or_else(String::new, |start| start.format("%H:%M").to_string()),
+                        end.format("%H:%M").to_string(),
+                    ],
+                    focus: 0,
+                });
+            }
+            KeyCode::Char('d') if self.selected < chunks => {
+                self.mode = Mode::ConfirmDelete;
+                self.message = "Delete the selected chunk? [y/n]".to_string();
+            }
+            KeyCode::Char('c') => return Some(Action::CheckIn),
+            KeyCode::Char('b') => return Some(Action::Break),
+            KeyCode::Char(c) => {
+                return hotkeys(self.config)
+                    .into_iter()
+                    .find(|quick_track| quick_track.key == c)
+                    .map(Action::Track);
+            }
+            _ => (),
+        }
+
+        None
+    }
+
+    /// Returns today with the edited chunk, or a message why the input is invalid.
+    fn apply_edit(&self, editor: &ChunkEditor) -> Result<Day, String> {
+        let mut day = self
+            .day
+            .clone()
+            .ok_or(Error::NotCheckedIn(self.now.date()).to_string())?;
+        let now = self.now.time();
+
+        let [project, description, start, end] = &editor.fields;
+
+        let start_time = match start.trim() {
+            "" => None,
+            start => Some(
+                parse_time_input(start)?
+                    .resolve(now, editor.span_start)
+                    .map_err(|e| e.to_string())?,
+            ),
+        };
+        let end_time = parse_time_input(end.trim())?
+            .resolve(now, editor.span_start)
+            .map_err(|e| e.to_string())?;
+
+        let edited = Chunk {
+            project: project.trim().to_string(),
+            description: Some(description.trim().to_string()).filter(|d| !d.is_empty()),
+            start_time,
+            end_time,
+            ..Chunk::default()
+        };
+        edit_chunk(&mut day, editor.index, edited, now, self.config).map_err(|e| e.to_string())?;
+
+        day.chunks.sort_by_key(|chunk| chunk.end_time);
+
+        Ok(day)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [tabs, status, main, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+
+        let selected_tab = match self.view {
+            View::Today => 0,
+            View::Week => 1,
+        };
+        frame.render_widget(
+            Tabs::new(vec!["Today", "Week"])
+                .select(selected_tab)
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            tabs,
+        );
+
+        let status_line = match &self.day {
+            Some(day) => {
+                format_short_status(&compute_status(day.clone(), self.now.time(), self.config))
+            }
+            None => "Not checked in, press c to check in".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(status_line)
+                .block(Block::default().borders(Borders::ALL).title("chron")),
+            status,
+        );
+
+        match self.view {
+            View::Today => self.render_today(frame, main),
+            View::Week => self.render_week(frame, main),
+        }
+
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(self.help()),
+                Line::from(self.message.as_str()),
+            ])
+            .block(Block::default().borders(Borders::TOP)),
+            footer,
+        );
+    }
+
+    fn render_today(&self, frame: &mut Frame, area: Rect) {
+        let table_area = if let Mode::Edit(editor) = &self.mode {
+            let [table_area, editor_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(6)]).areas(area);
+            render_editor(frame, editor_area, editor);
+            table_area
+        } else {
+            area
+        };
+
+        let rows: Vec<Row> = self
+            .chunk_rows()
+            .into_iter()
+            .map(|(start, end, project, description)| {
+                Row::new(vec![
+                    format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")),
+                    project.to_string(),
+                    description.to_string(),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(11),
+                Constraint::Length(20),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new(vec!["time", "project", "description"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title("Chunks"));
+
+        let mut state = TableState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(table, table_area, &mut state);
+    }
+
+    fn render_week(&self, frame: &mut Frame, area: Rect) {
+        let [days_area, summary_area] =
+            Layout::horizontal([Constraint::Length(30), Constraint::Min(0)]).areas(area);
+
+        let dates = get_days_of_week(self.now.date());
+        let days = load_available_days(&dates);
+
+        let rows: Vec<Row> = dates
+            .iter()
+            .map(|date| {
+                let work = days
+                    .iter()
+                    .find(|day| day.date == *date)
+                    .map_or(chrono::Duration::zero(), |day| {
+                        counted_work_duration(day, self.config)
+                    });
+                let target = self
+                    .config
+                    .target_for(*date)
+                    .map_or_else(String::new, |target| format_hours_minutes(&target));
+                Row::new(vec![
+                    date.format("%a %d.%m.").to_string(),
+                    format_hours_minutes(&work),
+                    target,
+                ])
+            })
+            .collect();
+
+        frame.render_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(11),
+                    Constraint::Length(8),
+                    Constraint::Length(8),
+                ],
+            )
+            .header(Row::new(vec!["day", "work", "target"]))
+            .block(Block::default().borders(Borders::ALL).title("Week")),
+            days_area,
+        );
+
+        frame.render_widget(
+            Paragraph::new(project_summary(
+                &days,
+                self.config,
+                &ReportOptions::default(),
+            ))
+            .block(Block::default().borders(Borders::ALL).title("Projects")),
+            summary_area,
+        );
+    }
+
+    fn help(&self) -> String {
+        match self.mode {
+            Mode::Edit(_) => "Tab next field · Enter save · Esc cancel".to_string(),
+            Mode::ConfirmDelete => "y delete · any other key cancels".to_string(),
+            Mode::Browse => {
+                let mut help = vec![
+                    "q quit".to_string(),
+                    "Tab week/today".to_string(),
+                    "↑↓ select".to_string(),
+                    "e edit".to_string(),
+                    "d delete".to_string(),
+                    "c check in".to_string(),
+                    "b break".to_string(),
+                ];
+                help.extend(
+                    hotkeys(self.config)
+                        .into_iter()
+                        .map(|quick_track| format!("{} {}", quick_track.key, quick_track.project)),
+                );
+                help.join(" · ")
+            }
+        }
+    }
+}
+
+fn render_editor(frame: &mut Frame, area: Rect, editor: &ChunkEditor) {
+    let lines: Vec<Line> = FIELDS
+        .iter()
+        .zip(&editor.fields)
+        .enumerate()
+        .map(|(index, (name, value))| {
+            let line = Line::from(format!("{name:>12}: {value}"));
+            if index == editor.focus {
+                line.style(Style::default().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Edit chunk")),
+        area,
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use chron::Project;
+    use chrono::NaiveDate;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn time(hours: u32, minutes: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hours, minutes, 0).unwrap()
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 11, 20).unwrap()
+    }
+
+    fn config() -> Config {
+        Config {
+            projects: vec![
+                Project::new("kyoshi".to_string()),
+                Project::new("korra".to_string()),
+            ],
+            ..Config::default()
+        }
+    }
+
+    fn app(config: &Config) -> App<'_> {
+        let chunk = |project: &str, description: Option<&str>, hour| Chunk {
+            project: project.to_string(),
+            description: description.map(str::to_string),
+            end_time: time(hour, 0),
+            ..Chunk::default()
+        };
+
+        let mut app = App::new(config, date().and_time(time(12, 0)));
+        app.day = Some(Day {
+            date: date(),
+            check_in_time: time(8, 0),
+            chunks: vec![chunk("kyoshi", Some("review"), 9), chunk("korra", None, 10)],
+            ..Day::default()
+        });
+        app
+    }
+
+    #[test]
+    fn test_hotkeys() {
+        let mut config = config();
+        let keys: Vec<_> = hotkeys(&config)
+            .into_iter()
+            .map(|quick_track| (quick_track.key, quick_track.project))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![('1', "kyoshi".to_string()), ('2', "korra".to_string())]
+        );
+
+        config.quick_track = vec![QuickTrack {
+            key: 'm',
+            project: "korra".to_string(),
+            description: Some("meeting".to_string()),
+        }];
+        assert_eq!(hotkeys(&config), config.quick_track);
+
+        let mut app = app(&config);
+        assert!(matches!(
+            app.handle_key(KeyCode::Char('m')),
+            Some(Action::Track(QuickTrack { project, .. })) if project == "korra"
+        ));
+        assert!(app.handle_key(KeyCode::Char('x')).is_none());
+    }
+
+    #[test]
+    fn test_delete_chunk() {
+        let config = config();
+        let mut app = app(&config);
+
+        app.handle_key(KeyCode::Char('d'));
+        let Some(Action::Save(day)) = app.handle_key(KeyCode::Char('y')) else {
+            panic!("the chunk should be deleted");
+        };
+        // the time of the deleted chunk becomes untracked
+        assert_eq!(day.chunks.len(), 1);
+        assert_eq!(day.chunks[0].project, "korra");
+        assert_eq!(day.chunks[0].start_time, Some(time(9, 0)));
+    }
+
+    #[test]
+    fn test_edit_chunk() {
+        let config = config();
+        let mut app = app(&config);
+
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Char('e'));
+        for _ in 0.."korra".len() {
+            app.handle_key(KeyCode::Backspace);
+        }
+        for c in "kyoshi".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Tab);
+        for c in "fix #bug".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+
+        // an end in the future is rejected and the editor stays open
+        app.handle_key(KeyCode::BackTab);
+        app.handle_key(KeyCode::BackTab);
+        for _ in 0.."10:00".len() {
+            app.handle_key(KeyCode::Backspace);
+        }
+        for c in "13:00".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        assert!(app.handle_key(KeyCode::Enter).is_none());
+        assert!(matches!(app.mode, Mode::Edit(_)));
+
+        app.handle_key(KeyCode::Backspace);
+        app.handle_key(KeyCode::Backspace);
+        app.handle_key(KeyCode::Backspace);
+        app.handle_key(KeyCode::Backspace);
+        for c in "0:30".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+
+        let Some(Action::Save(day)) = app.handle_key(KeyCode::Enter) else {
+            panic!("the chunk should be saved");
+        };
+        let chunk = &day.chunks[1];
+        assert_eq!(chunk.project, "kyoshi");
+        assert_eq!(chunk.description, Some("fix #bug".to_string()));
+        assert_eq!(chunk.tags, vec!["bug".to_string()]);
+        assert_eq!(chunk.end_time, time(10, 30));
+        assert_eq!(app.mode, Mode::Browse);
+    }
+
+    #[test]
+    fn test_edit_chunk_tags() {
+        let config = config();
+        let mut app = app(&config);
+        if let Some(day) = &mut app.day {
+            day.chunks[0].description = Some("review #old".to_string());
+            day.chunks[0].tags = vec!["old".to_string(), "explicit".to_string()];
+        }
+
+        app.handle_key(KeyCode::Char('e'));
+        app.handle_key(KeyCode::Tab);
+        for _ in 0.."review #old".len() {
+            app.handle_key(KeyCode::Backspace);
+        }
+        for c in "review #new".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+
+        let Some(Action::Save(day)) = app.handle_key(KeyCode::Enter) else {
+            panic!("the chunk should be saved");
+        };
+        assert_eq!(
+            day.chunks[0].tags,
+            vec!["new".to_string(), "explicit".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_render_today() {
+        let config = config();
+        let app = app(&config);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 15)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect();
+        assert!(screen.contains("2h 0m since 10:00 | today 4h 0m"));
+        assert!(screen.contains("08:00-09:00 kyoshi"));
+        assert!(screen.contains("review"));
+        assert!(screen.contains("09:00-10:00 korra"));
+        assert!(screen.contains("1 kyoshi"));
+    }
+}
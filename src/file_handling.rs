@@ -14,11 +14,9 @@ impl std::fmt::Display for DirsError<'_> {
     }
 }
 
-pub fn get_today_file_path<'a>() -> Result<PathBuf, DirsError<'a>> {
-    let now = chrono::offset::Local::now().naive_local().date();
-    get_file_path_for_date(now)
-}
-
+/// # Errors
+///
+/// Fails if the data directory of the platform is unknown.
 pub fn get_file_path_for_date<'a>(date: chrono::NaiveDate) -> Result<PathBuf, DirsError<'a>> {
     dirs::data_dir().ok_or(DirsError("data dir")).map(|dir| {
         dir.join(date.format(DATA_DIR_FORMAT).to_string())
@@ -26,12 +24,18 @@ pub fn get_file_path_for_date<'a>(date: chrono::NaiveDate) -> Result<PathBuf, Di
     })
 }
 
+/// # Errors
+///
+/// Fails if the config directory of the platform is unknown.
 pub fn get_config_file_path<'a>() -> Result<PathBuf, DirsError<'a>> {
     dirs::config_dir()
         .ok_or(DirsError("config dir"))
         .map(|dir| dir.join(DIR_NAME).join("config.json"))
 }
 
+/// # Errors
+///
+/// Fails if the path has no parent or the directory cannot be created.
 pub fn create_dir_if_not_exists(file_path: &Path) -> std::io::Result<()> {
     let dir = file_path.parent().ok_or(std::io::Error::new(
         std::io::ErrorKind::NotFound,
@@ -44,3 +48,32 @@ pub fn create_dir_if_not_exists(file_path: &Path) -> std::io::Result<()> {
 
     Ok(())
 }
+
+/// # Errors
+///
+/// Fails if the data directory of the platform is unknown.
+pub fn get_exported_worklogs_file_path<'a>() -> Result<PathBuf, DirsError<'a>> {
+    dirs::data_dir()
+        .ok_or(DirsError("data dir"))
+        .map(|dir| dir.join(DIR_NAME).join("exported-worklogs.json"))
+}
+
+/// # Errors
+///
+/// Fails if the data directory of the platform is unknown.
+pub fn get_prompt_cache_file_path<'a>() -> Result<PathBuf, DirsError<'a>> {
+    dirs::data_dir()
+        .ok_or(DirsError("data dir"))
+        .map(|dir| dir.join(DIR_NAME).join("prompt-cache.json"))
+}
+
+/// Replaces a leading `~` of the path with the home directory.
+#[must_use]
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,246 @@
+use crate::commands::{load_tracking_day, CommandExecutionError};
+use crate::config::{self, Config};
+use crate::file_handling::expand_home;
+use crate::suggest::{Proposal, Suggestions};
+use crate::time_input::last_chunk_end;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where `chron suggest --git` looks for commits.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConfig {
+    /// Author of the commits, the `user.email` of each repository if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Projects of the repositories by their path, e.g. `"~/code/kyoshi": "kyoshi"`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repos: BTreeMap<String, String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Commit {
+    time: NaiveTime,
+    project: String,
+    subject: String,
+}
+
+/// Proposes chunks from today's commits in the repositories since the end of the last
+/// chunk. Repositories without a project are left out with a warning.
+///
+/// # Errors
+///
+/// Fails if today was not checked in, git cannot be run, or if the config cannot be loaded.
+pub fn suggest_from_git(
+    repos: &[PathBuf],
+    now: NaiveDateTime,
+) -> Result<Suggestions, CommandExecutionError> {
+    let config = config::load_config()?;
+    let since = last_chunk_end(&load_tracking_day(now.date())?);
+
+    let mut commits = vec![];
+    let mut warnings = vec![];
+    for repo in repos {
+        let Some(project) = project_for_repo(&config, repo) else {
+            warnings.push(format!(
+                "No project is configured for {}, add it to git.repos in the config.",
+                repo.display()
+            ));
+            continue;
+        };
+
+        let author = match config.git.as_ref().and_then(|git| git.author.clone()) {
+            Some(author) => author,
+            None => run_git(repo, &["config", "user.email"])?.trim().to_string(),
+        };
+
+        let log = run_git(
+            repo,
+            &[
+                "log",
+                "--all",
+                "--no-merges",
+                &format!("--author={author}"),
+                &format!(
+                    "--since={}",
+                    now.date().and_time(since).format("%Y-%m-%dT%H:%M:%S")
+                ),
+                "--date=format-local:%Y-%m-%d %H:%M",
+                "--format=%ad\t%s",
+            ],
+        )?;
+
+        commits.extend(
+            parse_log(&log, now.date())
+                .into_iter()
+                .filter(|(time, _)| *time > since && *time <= now.time())
+                .map(|(time, subject)| Commit {
+                    time,
+                    project: project.clone(),
+                    subject,
+                }),
+        );
+    }
+
+    Ok(Suggestions {
+        proposals: proposals_from_commits(since, commits),
+        warnings,
+    })
+}
+
+/// Returns the project of the repository from the config, or the project named like its
+/// directory.
+fn project_for_repo(config: &Config, repo: &Path) -> Option<String> {
+    let repo = canonical(repo);
+
+    config
+        .git
+        .iter()
+        .flat_map(|git| &git.repos)
+        .find(|(path, _)| canonical(&expand_home(path)) == repo)
+        .map(|(_, project)| project.clone())
+        .or_else(|| {
+            let name = repo.file_name()?.to_str()?;
+            config.has_project(name).then(|| name.to_string())
+        })
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn run_git(repo: &Path, args: &[&str]) -> Result<String, CommandExecutionError> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(CommandExecutionError::UnexpectedError(format!(
+            "git failed in {}: {}",
+            repo.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parses lines like `2023-11-20 10:05<TAB>subject`, keeping the commits of the date.
+fn parse_log(log: &str, date: NaiveDate) -> Vec<(NaiveTime, String)> {
+    log.lines()
+        .filter_map(|line| {
+            let (time, subject) = line.split_once('\t')?;
+            let time = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").ok()?;
+            (time.date() == date).then(|| (time.time(), subject.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Proposes a chunk for each run of commits to the same project, ending with the last of
+/// them and described by their subjects.
+fn proposals_from_commits(since: NaiveTime, mut commits: Vec<Commit>) -> Vec<Proposal> {
+    commits.sort_by_key(|commit| commit.time);
+
+    let mut proposals: Vec<Proposal> = vec![];
+    for commit in commits {
+        match proposals.last_mut() {
+            Some(last) if last.project == commit.project => {
+                last.end = commit.time;
+                last.description = Some(match last.description.take() {
+                    Some(description) => format!("{description}; {}", commit.subject),
+                    None => commit.subject,
+                });
+            }
+            _ => proposals.push(Proposal {
+                start: proposals.last().map_or(since, |last| last.end),
+                end: commit.time,
+                project: commit.project,
+                description: Some(commit.subject),
+            }),
+        }
+    }
+
+    proposals.retain(|proposal| proposal.end > proposal.start);
+    proposals
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::config::Project;
+    use crate::test_util::{date, time};
+
+    #[test]
+    fn test_parse_log() {
+        let log = "2023-11-20 10:05\tFix the login\n2023-11-19 18:00\tOld commit\nbroken line\n";
+
+        assert_eq!(
+            parse_log(log, date()),
+            vec![(time(10, 5), "Fix the login".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_proposals_from_commits() {
+        let commit = |hours, minutes, project: &str, subject: &str| Commit {
+            time: time(hours, minutes),
+            project: project.to_string(),
+            subject: subject.to_string(),
+        };
+
+        let proposals = proposals_from_commits(
+            time(9, 0),
+            vec![
+                commit(10, 30, "korra", "Add the API"),
+                commit(9, 40, "kyoshi", "Fix the login"),
+                commit(10, 0, "kyoshi", "Add tests"),
+            ],
+        );
+
+        assert_eq!(
+            proposals,
+            vec![
+                Proposal {
+                    start: time(9, 0),
+                    end: time(10, 0),
+                    project: "kyoshi".to_string(),
+                    description: Some("Fix the login; Add tests".to_string()),
+                },
+                Proposal {
+                    start: time(10, 0),
+                    end: time(10, 30),
+                    project: "korra".to_string(),
+                    description: Some("Add the API".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_project_for_repo() {
+        let config = Config {
+            projects: vec![Project::new("kyoshi".to_string())],
+            git: Some(GitConfig {
+                author: None,
+                repos: BTreeMap::from([("/code/avatar".to_string(), "korra".to_string())]),
+            }),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            project_for_repo(&config, Path::new("/code/avatar")),
+            Some("korra".to_string())
+        );
+        assert_eq!(
+            project_for_repo(&config, Path::new("/code/kyoshi")),
+            Some("kyoshi".to_string())
+        );
+        assert_eq!(project_for_repo(&config, Path::new("/code/aang")), None);
+    }
+}
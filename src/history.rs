@@ -0,0 +1,425 @@
+use crate::commands::{load_tracking_day, CommandExecutionError};
+use crate::config;
+use crate::file_handling::expand_home;
+use crate::suggest::{untracked_gaps, Proposal, Suggestions};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::max;
+use std::path::{Component, Path, PathBuf};
+
+/// Where `chron suggest --history` reads the commands and how they map to projects.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryConfig {
+    /// The history file, `$HISTFILE`, `~/.zsh_history` or `~/.bash_history` if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<HistoryRule>,
+
+    /// Minutes before the first command of a run that count towards its chunk, e.g. for
+    /// reading or editing before running something.
+    #[serde(default, deserialize_with = "lead_minutes")]
+    pub lead_minutes: i64,
+}
+
+/// Longest lead before the first command of a run.
+const MAX_LEAD_MINUTES: i64 = 60;
+
+fn lead_minutes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    let minutes = i64::deserialize(deserializer)?;
+    if (0..=MAX_LEAD_MINUTES).contains(&minutes) {
+        Ok(minutes)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "leadMinutes must be between 0 and {MAX_LEAD_MINUTES}, not {minutes}"
+        )))
+    }
+}
+
+/// Maps commands to a project, by the directory they were run in and/or a regular
+/// expression for the command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    pub project: String,
+}
+
+struct CompiledRule<'a> {
+    directory: Option<PathBuf>,
+    command: Option<Regex>,
+    project: &'a str,
+}
+
+impl CompiledRule<'_> {
+    fn matches(&self, command: &str, directory: Option<&Path>) -> bool {
+        (self.directory.is_some() || self.command.is_some())
+            && self.directory.as_ref().is_none_or(|rule_directory| {
+                directory.is_some_and(|directory| directory.starts_with(rule_directory))
+            })
+            && self
+                .command
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(command))
+    }
+}
+
+/// Proposes chunks for the untracked gaps of today from the commands in the shell history.
+///
+/// # Errors
+///
+/// Fails if today was not checked in, no history file is found, a rule is not valid, or
+/// if the config cannot be loaded.
+pub fn suggest_from_history(
+    file: Option<&Path>,
+    now: NaiveDateTime,
+) -> Result<Suggestions, CommandExecutionError> {
+    let config = config::load_config()?;
+    let history = config.history.clone().unwrap_or_default();
+    let day = load_tracking_day(now.date())?;
+
+    let path = file
+        .map(Path::to_path_buf)
+        .or_else(|| history.file.as_deref().map(expand_home))
+        .or_else(default_history_file)
+        .ok_or(CommandExecutionError::UnexpectedError(
+            "No shell history was found, pass the file with --history <file>".to_string(),
+        ))?;
+    // zsh writes some bytes of non-ASCII commands differently, they are only needed for matching
+    let content = String::from_utf8_lossy(&std::fs::read(&path)?).to_string();
+
+    let rules = history
+        .rules
+        .iter()
+        .map(|rule| {
+            Ok(CompiledRule {
+                directory: rule.directory.as_deref().map(expand_home),
+                command: rule.command.as_deref().map(Regex::new).transpose()?,
+                project: &rule.project,
+            })
+        })
+        .collect::<Result<Vec<_>, regex::Error>>()
+        .map_err(|e| CommandExecutionError::InvalidHistoryRule(e.to_string()))?;
+    let mut warnings = vec![];
+    if rules.is_empty() {
+        warnings.push(
+            "No history rules are configured, add them to history.rules in the config.".to_string(),
+        );
+    }
+
+    let activity = activity(&parse_history(&content, &chrono::Local), &rules, now.date());
+
+    Ok(Suggestions {
+        proposals: proposals_in_gaps(
+            &untracked_gaps(&day, now.time()),
+            &activity,
+            Duration::minutes(history.lead_minutes),
+        ),
+        warnings,
+    })
+}
+
+fn default_history_file() -> Option<PathBuf> {
+    std::env::var("HISTFILE")
+        .ok()
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(["~/.zsh_history", "~/.bash_history"].map(expand_home))
+        .find(|path| path.exists())
+}
+
+/// Parses the commands with their time, from a zsh extended history with lines like
+/// `: 1700470000:0;git status`, or a bash history with `#1700470000` before each command.
+fn parse_history<Tz: TimeZone>(content: &str, timezone: &Tz) -> Vec<(NaiveDateTime, String)> {
+    let to_local = |seconds: &str| {
+        let seconds = seconds.parse().ok()?;
+        Some(timezone.timestamp_opt(seconds, 0).single()?.naive_local())
+    };
+
+    let mut entries = vec![];
+    let mut bash_time = None;
+
+    for line in content.lines() {
+        if let Some((meta, command)) = line
+            .strip_prefix(": ")
+            .and_then(|rest| rest.split_once(';'))
+        {
+            if let Some(time) = meta.split(':').next().and_then(to_local) {
+                entries.push((time, command.to_string()));
+            }
+        } else if let Some(time) = line.strip_prefix('#').and_then(to_local) {
+            bash_time = Some(time);
+        } else if let Some(time) = bash_time.take() {
+            entries.push((time, line.to_string()));
+        }
+    }
+
+    entries
+}
+
+/// Returns the times of the date at which a command matched a rule, with its project.
+///
+/// The directory of the commands is followed through the `cd` commands before them.
+fn activity(
+    entries: &[(NaiveDateTime, String)],
+    rules: &[CompiledRule],
+    date: NaiveDate,
+) -> Vec<(NaiveTime, String)> {
+    let mut directory = None;
+    let mut activity = vec![];
+
+    for (time, command) in entries {
+        directory = change_directory(command, directory);
+
+        if time.date() != date {
+            continue;
+        }
+        if let Some(rule) = rules
+            .iter()
+            .find(|rule| rule.matches(command, directory.as_deref()))
+        {
+            activity.push((time.time(), rule.project.to_string()));
+        }
+    }
+
+    activity.sort_by_key(|(time, _)| *time);
+    activity
+}
+
+/// Returns the directory after the command, which is unknown after a relative `cd` from an
+/// unknown directory.
+fn change_directory(command: &str, current: Option<PathBuf>) -> Option<PathBuf> {
+    let Some(rest) = command
+        .trim()
+        .strip_prefix("cd")
+        .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+    else {
+        return current;
+    };
+
+    let target = rest
+        .split(['&', ';', '|'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+
+    let path = match target {
+        "" => expand_home("~"),
+        "-" => return None,
+        target if target.starts_with('/') || target.starts_with('~') => expand_home(target),
+        target => current?.join(target),
+    };
+
+    // resolve `..` without touching the file system, the directory may not exist anymore
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => (),
+            component => normalized.push(component),
+        }
+    }
+    Some(normalized)
+}
+
+/// Proposes a chunk for each run of activity for the same project within the gaps, starting
+/// `lead` before the first command of the run and ending with the last one.
+///
+/// Expects a lead that is not negative.
+///
+/// The time in a gap without activity stays untracked.
+fn proposals_in_gaps(
+    gaps: &[(NaiveTime, NaiveTime)],
+    activity: &[(NaiveTime, String)],
+    lead: Duration,
+) -> Vec<Proposal> {
+    let mut proposals = vec![];
+
+    for &(gap_start, gap_end) in gaps {
+        let mut gap_proposals: Vec<Proposal> = vec![];
+
+        for (time, project) in activity
+            .iter()
+            .filter(|(time, _)| *time > gap_start && *time <= gap_end)
+        {
+            match gap_proposals.last_mut() {
+                Some(last) if last.project == *project => last.end = *time,
+                last => {
+                    let earliest = last.map_or(gap_start, |last| last.end);
+                    // a lead reaching before midnight starts at the earliest possible time
+                    let (start, wrapped) = time.overflowing_sub_signed(lead);
+                    let start = if wrapped == 0 {
+                        max(start, earliest)
+                    } else {
+                        earliest
+                    };
+                    gap_proposals.push(Proposal {
+                        start,
+                        end: *time,
+                        project: project.clone(),
+                        description: None,
+                    });
+                }
+            }
+        }
+
+        proposals.extend(
+            gap_proposals
+                .into_iter()
+                .filter(|proposal| proposal.start < proposal.end),
+        );
+    }
+
+    proposals
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_util::{date, time};
+    use chrono::Utc;
+
+    fn at(hours: u32, minutes: u32) -> NaiveDateTime {
+        date().and_time(time(hours, minutes))
+    }
+
+    #[test]
+    fn test_parse_history() {
+        let zsh = ": 1700470800:0;cd /code/kyoshi\n: 1700472600:3;cargo test\ncontinued line\n";
+        assert_eq!(
+            parse_history(zsh, &Utc),
+            vec![
+                (at(9, 0), "cd /code/kyoshi".to_string()),
+                (at(9, 30), "cargo test".to_string()),
+            ]
+        );
+
+        let bash = "#1700470800\ncd /code/kyoshi\n#1700472600\ncargo test\nno timestamp\n";
+        assert_eq!(parse_history(bash, &Utc), parse_history(zsh, &Utc));
+    }
+
+    #[test]
+    fn test_change_directory() {
+        let current = Some(PathBuf::from("/code/kyoshi"));
+
+        assert_eq!(
+            change_directory("cd ../korra && make", current.clone()),
+            Some(PathBuf::from("/code/korra"))
+        );
+        assert_eq!(
+            change_directory("cd /tmp", None),
+            Some(PathBuf::from("/tmp"))
+        );
+        assert_eq!(change_directory("cd src", None), None);
+        assert_eq!(change_directory("cdk deploy", current.clone()), current);
+        assert_eq!(change_directory("cd -", current), None);
+    }
+
+    #[test]
+    fn test_activity_and_proposals() {
+        let entries = vec![
+            (
+                at(8, 0) - chrono::Duration::days(1),
+                "cd /code/kyoshi".to_string(),
+            ),
+            (at(9, 10), "cargo test".to_string()),
+            (at(9, 30), "git commit".to_string()),
+            (at(9, 40), "ssh korra-prod".to_string()),
+            (at(9, 45), "ls".to_string()),
+            (at(10, 30), "cd /tmp".to_string()),
+            (at(10, 40), "ls".to_string()),
+        ];
+        let rules = vec![
+            CompiledRule {
+                directory: None,
+                command: Some(Regex::new("^ssh korra").unwrap()),
+                project: "korra",
+            },
+            CompiledRule {
+                directory: Some(PathBuf::from("/code/kyoshi")),
+                command: None,
+                project: "kyoshi",
+            },
+        ];
+
+        let activity = activity(&entries, &rules, at(0, 0).date());
+        assert_eq!(
+            activity,
+            vec![
+                (time(9, 10), "kyoshi".to_string()),
+                (time(9, 30), "kyoshi".to_string()),
+                (time(9, 40), "korra".to_string()),
+                (time(9, 45), "kyoshi".to_string()),
+            ]
+        );
+
+        let proposal = |start, end, project: &str| Proposal {
+            start,
+            end,
+            project: project.to_string(),
+            description: None,
+        };
+        assert_eq!(
+            proposals_in_gaps(
+                &[(time(9, 0), time(9, 35)), (time(9, 40), time(12, 0))],
+                &activity,
+                Duration::minutes(5)
+            ),
+            vec![
+                proposal(time(9, 5), time(9, 30), "kyoshi"),
+                proposal(time(9, 40), time(9, 45), "kyoshi"),
+            ]
+        );
+
+        // without a lead, a single command makes no chunk
+        assert_eq!(
+            proposals_in_gaps(&[(time(9, 0), time(12, 0))], &activity, Duration::zero()),
+            vec![proposal(time(9, 10), time(9, 30), "kyoshi")]
+        );
+    }
+
+    #[test]
+    fn test_lead_reaching_before_midnight() {
+        let activity = vec![
+            (time(0, 10), "kyoshi".to_string()),
+            (time(0, 20), "kyoshi".to_string()),
+        ];
+
+        assert_eq!(
+            proposals_in_gaps(
+                &[(time(0, 0), time(1, 0))],
+                &activity,
+                Duration::minutes(30)
+            ),
+            vec![Proposal {
+                start: time(0, 0),
+                end: time(0, 20),
+                project: "kyoshi".to_string(),
+                description: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_lead_minutes_from_json() {
+        let history: HistoryConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(history.lead_minutes, 0);
+
+        let history: HistoryConfig = serde_json::from_str(r#"{"leadMinutes": 5}"#).unwrap();
+        assert_eq!(history.lead_minutes, 5);
+
+        assert!(serde_json::from_str::<HistoryConfig>(r#"{"leadMinutes": -5}"#).is_err());
+        assert!(serde_json::from_str::<HistoryConfig>(r#"{"leadMinutes": 61}"#).is_err());
+    }
+}
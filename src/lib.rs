@@ -0,0 +1,61 @@
+//! Time tracking in chunks, with the days stored as JSON files in the data directory.
+//!
+//! The library loads and saves the days and the config, tracks chunks and summarizes days.
+//! Functions that depend on the current time take it as an argument, and none of them print:
+//! the rules a day violates after tracking are returned as warnings.
+//!
+//! The `chron` binary adds the command line, the dashboard, the HTTP API and the commands
+//! that ask questions on the terminal or count down on top.
+
+#![deny(clippy::unwrap_used)]
+#![warn(clippy::pedantic)]
+
+mod balance;
+mod commands;
+mod config;
+mod file_handling;
+mod git;
+mod history;
+mod pomodoro;
+mod prompt;
+mod remind;
+mod reporting;
+mod rounding;
+mod rules;
+mod schedule;
+mod status;
+mod suggest;
+mod tags;
+#[cfg(test)]
+mod test_util;
+mod tickets;
+mod time_input;
+mod worklog;
+
+pub use balance::{compute_balance, report_balance, Balance};
+pub use commands::{
+    absent, check_in, check_project_configured, delete_chunk, edit_chunk, load_day,
+    load_tracking_day, reset, save_day, start, stop, take_break, track, track_chunks,
+    track_durations, Chunk, CommandExecutionError as Error, Day, DurationChunk, GroupBy, Period,
+    ReportOptions, RunningChunk,
+};
+pub use config::{
+    add_project, delete_project, load_config, save_config, Config, Project, QuickTrack,
+};
+pub use git::suggest_from_git;
+pub use history::suggest_from_history;
+pub use prompt::prompt;
+pub use remind::{due_reminder, Reminder, SentReminders};
+pub use reporting::{
+    counted_work_duration, day_spans, get_days_of_month, get_days_of_week, load_available_days,
+    project_summary, report_day, report_month, report_week, summarize, summary_json, work_duration,
+    Span, Summary, UNTRACKED,
+};
+pub use rules::{check_day, Violation};
+pub use schedule::{apply_schedule, AppliedSchedule, Skipped};
+pub use status::{
+    compute_status, format_hours_minutes, format_short_status, format_status, status_json, Status,
+};
+pub use suggest::{untracked_gaps, Proposal, Suggestions};
+pub use time_input::{last_chunk_end, parse_duration, parse_time_input, TimeInput};
+pub use worklog::{export_worklogs, Export};
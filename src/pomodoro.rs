@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Lengths of the intervals of `chron pomodoro`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PomodoroConfig {
+    #[serde(default = "default_work_minutes")]
+    pub work_minutes: i64,
+
+    #[serde(default = "default_break_minutes")]
+    pub break_minutes: i64,
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        PomodoroConfig {
+            work_minutes: default_work_minutes(),
+            break_minutes: default_break_minutes(),
+        }
+    }
+}
+
+fn default_work_minutes() -> i64 {
+    25
+}
+
+fn default_break_minutes() -> i64 {
+    5
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pomodoro_config() {
+        let config: PomodoroConfig = serde_json::from_str(r#"{ "workMinutes": 50 }"#).unwrap();
+        assert_eq!(
+            config,
+            PomodoroConfig {
+                work_minutes: 50,
+                break_minutes: 5,
+            }
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use crate::commands::{date_format, optional_time_format, Day};
+use crate::file_handling;
+use crate::time_input::last_chunk_end;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::UNIX_EPOCH;
+
+const NOT_CHECKED_IN: &str = "⚠ no check-in";
+
+/// What the prompt shows of a day, cached as long as the file of the day is unchanged.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PromptCache {
+    #[serde(with = "date_format")]
+    date: NaiveDate,
+
+    /// Modification time of the file of the day in milliseconds since the epoch.
+    modified: u128,
+
+    /// The running project or the project of the last chunk.
+    project: Option<String>,
+
+    /// Start of the current chunk, `None` if the day is marked absent.
+    #[serde(with = "optional_time_format")]
+    since: Option<NaiveTime>,
+}
+
+/// Returns a single line for shell prompts, without loading the config.
+///
+/// Never fails, a missing data directory or check-in is shown in the line.
+#[must_use]
+pub fn prompt(now: NaiveDateTime) -> String {
+    prompt_state(now.date()).map_or(NOT_CHECKED_IN.to_string(), |state| {
+        format_prompt(&state, now.time())
+    })
+}
+
+/// Returns the state of the day from the cache, or from the file of the day if it changed.
+fn prompt_state(date: NaiveDate) -> Option<PromptCache> {
+    let day_path = file_handling::get_file_path_for_date(date).ok()?;
+    let modified = fs::metadata(&day_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_millis();
+
+    let cache_path = file_handling::get_prompt_cache_file_path().ok()?;
+    if let Some(cache) = fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<PromptCache>(&content).ok())
+        .filter(|cache| cache.date == date && cache.modified == modified)
+    {
+        return Some(cache);
+    }
+
+    let day: Day = serde_json::from_str(&fs::read_to_string(&day_path).ok()?).ok()?;
+    let cache = day_state(&day, modified);
+
+    if let Ok(content) = serde_json::to_string(&cache) {
+        // the prompt works without the cache as well
+        let _ = fs::write(cache_path, content);
+    }
+
+    Some(cache)
+}
+
+fn day_state(day: &Day, modified: u128) -> PromptCache {
+    let (project, since) = if day.absence.is_some() {
+        (None, None)
+    } else if let Some(running) = &day.running {
+        (Some(running.project.clone()), Some(running.start_time))
+    } else {
+        (
+            day.chunks
+                .iter()
+                .max_by_key(|chunk| chunk.end_time)
+                .map(|chunk| chunk.project.clone()),
+            Some(last_chunk_end(day)),
+        )
+    };
+
+    PromptCache {
+        date: day.date,
+        modified,
+        project,
+        since,
+    }
+}
+
+fn format_prompt(state: &PromptCache, now: NaiveTime) -> String {
+    let Some(since) = state.since else {
+        return String::new();
+    };
+    let minutes = (now - since).num_minutes().max(0);
+
+    match &state.project {
+        Some(project) => format!("{project} {minutes}m"),
+        None => format!("{minutes}m"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::commands::RunningChunk;
+    use crate::test_util::{chunk, date, day, time};
+
+    #[test]
+    fn test_prompt() {
+        let mut day = day(vec![]);
+        let format = |day: &Day| format_prompt(&day_state(day, 0), time(10, 15));
+
+        assert_eq!(format(&day), "135m");
+
+        day.chunks.push(chunk("kyoshi", time(10, 0)));
+        assert_eq!(format(&day), "kyoshi 15m");
+
+        day.running = Some(RunningChunk {
+            project: "korra".to_string(),
+            start_time: time(10, 5),
+            ..RunningChunk::default()
+        });
+        assert_eq!(format(&day), "korra 10m");
+
+        day.absence = Some("vacation".to_string());
+        assert_eq!(format(&day), "");
+    }
+
+    #[test]
+    fn test_prompt_cache() {
+        let cache = PromptCache {
+            date: date(),
+            modified: 1_700_000_000_000,
+            project: Some("kyoshi".to_string()),
+            since: Some(time(10, 0)),
+        };
+
+        let serialized = serde_json::to_string(&cache).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PromptCache>(&serialized).unwrap(),
+            cache
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::commands::Day;
+use crate::config::Config;
+use crate::time_input::last_chunk_end;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// When `chron remind` reminds to track or to check in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminders {
+    /// Minutes since the end of the last chunk after which a reminder is sent, and again
+    /// every time this interval passes.
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: i64,
+
+    /// Warns at this time if today was not checked in yet, on days with a target.
+    #[serde(
+        default,
+        with = "crate::commands::optional_time_format",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub check_in_by: Option<NaiveTime>,
+
+    /// No reminders are sent after this time.
+    #[serde(
+        default,
+        with = "crate::commands::optional_time_format",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub quiet_after: Option<NaiveTime>,
+}
+
+impl Default for Reminders {
+    fn default() -> Self {
+        Reminders {
+            interval_minutes: default_interval_minutes(),
+            check_in_by: None,
+            quiet_after: None,
+        }
+    }
+}
+
+fn default_interval_minutes() -> i64 {
+    120
+}
+
+/// A reminder of `chron remind`.
+#[derive(Debug, PartialEq)]
+pub enum Reminder {
+    /// Nothing was tracked for the duration.
+    Idle(Duration),
+    NotCheckedIn,
+}
+
+impl std::fmt::Display for Reminder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reminder::Idle(duration) => {
+                let hours = duration.num_hours();
+                let minutes = duration.num_minutes() - hours * 60;
+                write!(
+                    f,
+                    "Nothing was tracked for {hours}h {minutes}m, run chron track"
+                )
+            }
+            Reminder::NotCheckedIn => write!(f, "You did not check in today, run chron check-in"),
+        }
+    }
+}
+
+/// The reminders that were already sent.
+#[derive(Debug, Default)]
+pub struct SentReminders {
+    check_in_warning: Option<NaiveDate>,
+    idle: Option<NaiveDateTime>,
+}
+
+/// Returns the reminder that is due now, if any, and records it as sent.
+///
+/// `day` is today, `None` if it was not checked in yet.
+pub fn due_reminder(
+    day: Option<&Day>,
+    now: NaiveDateTime,
+    config: &Config,
+    reminders: &Reminders,
+    sent: &mut SentReminders,
+) -> Option<Reminder> {
+    if reminders
+        .quiet_after
+        .is_some_and(|quiet_after| now.time() >= quiet_after)
+    {
+        return None;
+    }
+
+    let Some(day) = day else {
+        let check_in_by = reminders.check_in_by?;
+        let works_today = config
+            .target_for(now.date())
+            .is_none_or(|target| target > Duration::zero());
+
+        if now.time() < check_in_by || !works_today || sent.check_in_warning == Some(now.date()) {
+            return None;
+        }
+        sent.check_in_warning = Some(now.date());
+        return Some(Reminder::NotCheckedIn);
+    };
+
+    if day.absence.is_some() || day.running.is_some() {
+        return None;
+    }
+
+    let interval = Duration::minutes(reminders.interval_minutes);
+    let last_end = day.date.and_time(last_chunk_end(day));
+    let last_reminder = sent
+        .idle
+        .filter(|idle| *idle > last_end)
+        .unwrap_or(last_end);
+
+    if now - last_reminder < interval {
+        return None;
+    }
+    sent.idle = Some(now);
+    Some(Reminder::Idle(now - last_end))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_util::{chunk, date, day, time};
+
+    fn at(hours: u32, minutes: u32) -> NaiveDateTime {
+        date().and_time(time(hours, minutes))
+    }
+
+    #[test]
+    fn test_idle_reminders() {
+        let config = Config::default();
+        let reminders = Reminders {
+            quiet_after: Some(time(18, 0)),
+            ..Reminders::default()
+        };
+        let mut sent = SentReminders::default();
+        let day = day(vec![chunk("kyoshi", time(9, 0))]);
+
+        let mut due = |now| due_reminder(Some(&day), now, &config, &reminders, &mut sent);
+
+        assert_eq!(due(at(10, 59)), None);
+        assert_eq!(due(at(11, 0)), Some(Reminder::Idle(Duration::hours(2))));
+        assert_eq!(due(at(11, 1)), None);
+        assert_eq!(due(at(12, 59)), None);
+        assert_eq!(due(at(13, 0)), Some(Reminder::Idle(Duration::hours(4))));
+        assert_eq!(due(at(18, 0)), None);
+    }
+
+    #[test]
+    fn test_check_in_reminder() {
+        let mut config = Config {
+            daily_target_hours: Some(8.0),
+            ..Config::default()
+        };
+        let reminders = Reminders {
+            check_in_by: Some(time(9, 30)),
+            ..Reminders::default()
+        };
+        let mut sent = SentReminders::default();
+
+        assert_eq!(
+            due_reminder(None, at(9, 0), &config, &reminders, &mut sent),
+            None
+        );
+        assert_eq!(
+            due_reminder(None, at(9, 30), &config, &reminders, &mut sent),
+            Some(Reminder::NotCheckedIn)
+        );
+        assert_eq!(
+            due_reminder(None, at(9, 31), &config, &reminders, &mut sent),
+            None
+        );
+
+        // no work expected on this day
+        config.daily_target_hours = None;
+        config.target_hours = Some(crate::config::WeekdayHours::default());
+        let mut sent = SentReminders::default();
+        assert_eq!(
+            due_reminder(None, at(9, 30), &config, &reminders, &mut sent),
+            None
+        );
+    }
+}
@@ -1,19 +1,76 @@
-use crate::commands::{load_day, CommandExecutionError, Day};
-use chrono::{Datelike, NaiveDate};
+use crate::balance::overtime_section;
+use crate::commands::{load_day, CommandExecutionError, Day, GroupBy, ReportOptions};
+use crate::config::{self, Config};
+use crate::rounding::RoundingScope;
+use crate::rules::{break_adjustments, check_day};
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use serde_json::json;
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-pub fn report_day(date: NaiveDate) -> Result<(), CommandExecutionError> {
+/// Returns the report of a day.
+///
+/// # Errors
+///
+/// Fails if the day was not checked in or the config cannot be loaded.
+pub fn report_day(
+    date: NaiveDate,
+    options: &ReportOptions,
+) -> Result<String, CommandExecutionError> {
     let day = load_day(date)?;
+    let config = config::load_config()?;
+
+    if options.invoice {
+        let mut day = day;
+        day.chunks.sort_by_key(|chunk| chunk.end_time);
+        let title = day.date.format("%Y-%m-%d").to_string();
+        return Ok(format_invoice(&title, &[day], &config));
+    }
+
+    let warnings = format_warnings(std::slice::from_ref(&day), &config, false);
+    let formatted_day = format_day(day, &config, options);
+    Ok(format!("{formatted_day}{warnings}"))
+}
+
+/// Returns the report of the week containing the date, with the overtime until today.
+///
+/// # Errors
+///
+/// Fails if the config cannot be loaded.
+pub fn report_week(
+    date: NaiveDate,
+    today: NaiveDate,
+    options: &ReportOptions,
+) -> Result<String, CommandExecutionError> {
+    let weekdays = get_days_of_week(date);
+    let days: Vec<Day> = load_available_days(&weekdays);
+    let config = config::load_config()?;
+
+    if options.invoice {
+        let title = date.format("week %W of %Y").to_string();
+        return Ok(format_invoice(&title, &days, &config));
+    }
 
-    let formatted_day = format_day(day);
-    println!("{formatted_day}");
+    Ok(format!(
+        "# Log for: {}
 
-    Ok(())
+{}{}{}{}",
+        date.format("week %W of %Y"),
+        project_summary(&days, &config, options),
+        format_grouping(&days, options),
+        format_overtime(&weekdays, &days, &config, today),
+        format_warnings(&days, &config, true)
+    ))
 }
 
-pub fn report_week(date: NaiveDate) {
-    let weekdays: Vec<NaiveDate> = [
+/// Returns the days from Monday to Sunday of the week containing the date.
+///
+/// # Panics
+///
+/// Panics if the week is outside of the dates supported by `chrono`.
+#[must_use]
+pub fn get_days_of_week(date: NaiveDate) -> Vec<NaiveDate> {
+    [
         chrono::Weekday::Mon,
         chrono::Weekday::Tue,
         chrono::Weekday::Wed,
@@ -27,108 +84,505 @@ pub fn report_week(date: NaiveDate) {
         NaiveDate::from_isoywd_opt(date.year(), date.iso_week().week(), wd)
             .expect("date should be valid")
     })
-    .collect();
+    .collect()
+}
 
-    let days: Vec<Day> = load_available_days(&weekdays);
+/// Returns the section for the additional grouping of the report, if one was requested.
+fn format_grouping(days: &[Day], options: &ReportOptions) -> String {
+    match options.group_by {
+        GroupBy::Project => String::new(),
+        GroupBy::Tag => format!("\n\n{}", tag_summary(days)),
+        GroupBy::Ticket => format!("\n\n{}", ticket_summary(days)),
+    }
+}
 
-    println!(
-        "# Log for: {}
+/// Pseudo tag for work chunks without any tags.
+const UNTAGGED: &str = "untagged";
 
-{}",
-        date.format("week %W of %Y"),
-        project_summary(&days)
-    );
+/// Pseudo ticket for work chunks without any ticket references.
+const NO_TICKET: &str = "no ticket";
+
+/// Returns a summary of the work per tag over the given days.
+///
+/// Chunks with several tags count for each of them. Breaks and untracked time are left out.
+/// Expects the chunks for each day to be sorted by end time.
+fn tag_summary(days: &[Day]) -> String {
+    let rows = label_rows(days, UNTAGGED, |span| span.tags);
+    format!("## tags\n\n{}", format_table(&["tag", "time"], &rows))
 }
 
-fn load_available_days(dates: &[NaiveDate]) -> Vec<Day> {
+/// Returns a summary of the work per ticket over the given days.
+///
+/// Chunks referencing several tickets count for each of them.
+/// Expects the chunks for each day to be sorted by end time.
+fn ticket_summary(days: &[Day]) -> String {
+    let rows = label_rows(days, NO_TICKET, |span| span.tickets);
+    format!("## tickets\n\n{}", format_table(&["ticket", "time"], &rows))
+}
+
+/// Returns the table rows with the work per label, sorted by label,
+/// followed by the work without any label.
+fn label_rows(
+    days: &[Day],
+    unlabeled: &str,
+    labels: for<'a> fn(&Span<'a>) -> &'a [String],
+) -> Vec<Vec<String>> {
+    let mut label_durations: HashMap<String, chrono::Duration> = HashMap::new();
+
+    for day in days {
+        for span in day_spans(day).iter().filter(|span| is_work(span.project)) {
+            if labels(span).is_empty() {
+                add_duration(&mut label_durations, unlabeled, span.end - span.start);
+            }
+            for label in labels(span) {
+                add_duration(&mut label_durations, label, span.end - span.start);
+            }
+        }
+    }
+
+    let unlabeled_duration = label_durations.remove(unlabeled);
+
+    let mut sorted_labels: Vec<(String, chrono::Duration)> = label_durations.into_iter().collect();
+    sorted_labels.sort();
+    sorted_labels.extend(unlabeled_duration.map(|duration| (unlabeled.to_string(), duration)));
+
+    sorted_labels
+        .iter()
+        .map(|(label, duration)| vec![label.clone(), format_duration(duration)])
+        .collect()
+}
+
+/// Returns a section listing the rule violations of the given days, if there are any.
+fn format_warnings(days: &[Day], config: &Config, with_date: bool) -> String {
+    let warnings: Vec<String> = days
+        .iter()
+        .flat_map(|day| {
+            check_day(day, &config.rules)
+                .into_iter()
+                .map(move |violation| {
+                    if with_date {
+                        format!("- {}: {violation}", day.date.format("%Y-%m-%d"))
+                    } else {
+                        format!("- {violation}")
+                    }
+                })
+        })
+        .collect();
+
+    if warnings.is_empty() {
+        String::new()
+    } else {
+        format!("\n\n## warnings\n\n{}", warnings.join("\n"))
+    }
+}
+
+fn format_overtime(dates: &[NaiveDate], days: &[Day], config: &Config, today: NaiveDate) -> String {
+    overtime_section(dates, days, config, today)
+        .map_or(String::new(), |section| format!("\n\n{section}"))
+}
+
+/// Loads the days that were checked in, with their chunks sorted by end time.
+#[must_use]
+pub fn load_available_days(dates: &[NaiveDate]) -> Vec<Day> {
     dates
         .iter()
         .filter_map(|date| {
             load_day(*date).ok().map(|mut day| {
-                day.chunks.sort_by(|a, b| a.end_time.cmp(&b.end_time));
+                day.chunks.sort_by_key(|chunk| chunk.end_time);
                 day
             })
         })
         .collect()
 }
 
-pub fn report_month(date: NaiveDate) {
+/// Returns the report of the month containing the date, with the overtime until today.
+///
+/// # Errors
+///
+/// Fails if the config cannot be loaded.
+pub fn report_month(
+    date: NaiveDate,
+    today: NaiveDate,
+    options: &ReportOptions,
+) -> Result<String, CommandExecutionError> {
     let days_of_month = get_days_of_month(date);
     let days = load_available_days(&days_of_month);
+    let config = config::load_config()?;
+
+    if options.invoice {
+        let title = date.format("%B of %Y").to_string();
+        return Ok(format_invoice(&title, &days, &config));
+    }
 
-    println!(
+    Ok(format!(
         "# Log for: {}
 
-{}",
+{}{}{}",
         date.format("%B of %Y"),
-        project_summary(&days)
-    );
+        project_summary(&days, &config, options),
+        format_grouping(&days, options),
+        format_overtime(&days_of_month, &days, &config, today)
+    ))
 }
 
-fn get_days_of_month(date: NaiveDate) -> Vec<NaiveDate> {
+#[must_use]
+pub fn get_days_of_month(date: NaiveDate) -> Vec<NaiveDate> {
     (1..=31)
         .filter_map(|day| NaiveDate::from_ymd_opt(date.year(), date.month(), day))
         .collect::<Vec<NaiveDate>>()
 }
 
-fn format_day(mut day: Day) -> String {
-    day.chunks.sort_by(|a, b| a.end_time.cmp(&b.end_time));
+fn format_day(mut day: Day, config: &Config, options: &ReportOptions) -> String {
+    day.chunks.sort_by_key(|chunk| chunk.end_time);
+
+    if let Some(reason) = &day.absence {
+        return format!(
+            "# Log for: {}
+
+absent: {reason}",
+            day.date.format("%Y-%m-%d")
+        );
+    }
 
     format!(
         "# Log for: {}
 
-{}
+{}{}
 
 {}",
         day.date.format("%Y-%m-%d"),
-        project_summary(&vec![day.clone()]),
+        project_summary(std::slice::from_ref(&day), config, options),
+        format_grouping(std::slice::from_ref(&day), options),
         detail_table(&day)
     )
 }
 
-/// Returns a summary of projects over the given days.
+/// Pseudo project for the time between two chunks that was not tracked at all.
+pub const UNTRACKED: &str = "untracked";
+
+/// A continuous piece of time of a day, either a tracked chunk or an untracked gap.
+pub struct Span<'a> {
+    pub project: &'a str,
+    pub description: Option<&'a String>,
+    pub tags: &'a [String],
+    pub tickets: &'a [String],
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// Returns the spans of a day, including untracked gaps.
+///
+/// A chunk without a start time starts where the previous one ended.
+/// Expects the chunks to be sorted by end time.
+#[must_use]
+pub fn day_spans(day: &Day) -> Vec<Span<'_>> {
+    let mut spans = vec![];
+    let mut previous_chunk_end_time = day.check_in_time;
+
+    for chunk in &day.chunks {
+        let start = chunk.start_time.unwrap_or(previous_chunk_end_time);
+
+        if start > previous_chunk_end_time {
+            spans.push(Span {
+                project: UNTRACKED,
+                description: None,
+                tags: &[],
+                tickets: &[],
+                start: previous_chunk_end_time,
+                end: start,
+            });
+        }
+
+        spans.push(Span {
+            project: &chunk.project,
+            description: chunk.description.as_ref(),
+            tags: &chunk.tags,
+            tickets: &chunk.tickets,
+            start,
+            end: chunk.end_time,
+        });
+
+        previous_chunk_end_time = chunk.end_time;
+    }
+
+    spans
+}
+
+/// Returns the time worked on a day, i.e. without breaks and untracked gaps.
+///
+/// Expects the chunks to be sorted by end time.
+#[must_use]
+pub fn work_duration(day: &Day) -> chrono::Duration {
+    day_spans(day)
+        .iter()
+        .filter(|span| is_work(span.project))
+        .map(|span| span.end - span.start)
+        .sum()
+}
+
+/// Returns the time worked on a day like [`work_duration`], minus the missing break
+/// if a `breakDeduction` is configured, so it matches the reports.
+///
+/// Expects the chunks to be sorted by end time.
+#[must_use]
+pub fn counted_work_duration(day: &Day, config: &Config) -> chrono::Duration {
+    let deducted: chrono::Duration = config
+        .break_deduction
+        .map(|policy| break_adjustments(day, &config.rules, policy))
+        .unwrap_or_default()
+        .iter()
+        .map(|adjustment| adjustment.duration)
+        .sum();
+
+    work_duration(day) - deducted
+}
+
+/// Whether time spent on the project counts as work, i.e. it's neither a break nor untracked.
+#[must_use]
+pub fn is_work(project: &str) -> bool {
+    project != "break" && project != UNTRACKED
+}
+
+/// Returns the durations of all breaks of a day.
+///
+/// Expects the chunks to be sorted by end time.
+#[must_use]
+pub fn break_durations(day: &Day) -> Vec<chrono::Duration> {
+    day_spans(day)
+        .iter()
+        .filter(|span| span.project == "break")
+        .map(|span| span.end - span.start)
+        .collect()
+}
+
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn format_duration(duration: &chrono::Duration) -> String {
+    let fraction = duration.num_minutes() as f64 / 60.0;
+    let hours = duration.num_hours();
+    let minutes = duration.num_minutes() - hours * 60;
+    format!("{fraction:.2}h ({hours}h {minutes}m)")
+}
+
+/// Times per project over a number of days.
+struct ProjectDurations {
+    raw: HashMap<String, chrono::Duration>,
+    /// Rounded times of the projects that have a rounding configured.
+    rounded: HashMap<String, chrono::Duration>,
+    /// Descriptions of the time that was counted as break by the break deduction.
+    adjustments: Vec<String>,
+}
+
+/// Sums up the time per project over the given days.
 ///
 /// Expects the chunks for each day to be sorted by end time.
-fn project_summary(days: &Vec<Day>) -> String {
-    let mut project_durations: HashMap<String, chrono::Duration> = HashMap::new();
+fn project_durations(days: &[Day], config: &Config) -> ProjectDurations {
+    let mut durations = ProjectDurations {
+        raw: HashMap::new(),
+        rounded: HashMap::new(),
+        adjustments: vec![],
+    };
 
     for day in days {
-        let mut previous_chunk_end_time = day.check_in_time;
+        let spans = day_spans(day);
+        let mut chunk_durations: Vec<(&str, chrono::Duration)> = spans
+            .iter()
+            .map(|span| (span.project, span.end - span.start))
+            .collect();
+
+        if let Some(policy) = config.break_deduction {
+            for adjustment in break_adjustments(day, &config.rules, policy) {
+                // the adjusted time is taken from the chunk it starts in
+                if let Some(index) = spans.iter().position(|span| {
+                    span.project == adjustment.project
+                        && span.start <= adjustment.start
+                        && adjustment.start < span.end
+                }) {
+                    chunk_durations[index].1 = chunk_durations[index].1 - adjustment.duration;
+                }
+                chunk_durations.push(("break", adjustment.duration));
+
+                durations.adjustments.push(format!(
+                    "- {} {}: {} of {} counted as break ({})",
+                    day.date.format("%Y-%m-%d"),
+                    adjustment.start.format("%H:%M"),
+                    format_duration(&adjustment.duration),
+                    adjustment.project,
+                    adjustment.reason
+                ));
+            }
+        }
 
-        for chunk in &day.chunks {
-            let project_duration = project_durations
-                .entry(chunk.project.clone())
-                .or_insert(chrono::Duration::zero());
+        let mut day_durations = HashMap::new();
+        for (project, duration) in chunk_durations {
+            add_duration(&mut durations.raw, project, duration);
+            add_duration(&mut day_durations, project, duration);
 
-            *project_duration = *project_duration + (chunk.end_time - previous_chunk_end_time);
+            match config.rounding_for(project) {
+                Some(rounding) if rounding.scope == RoundingScope::Chunk => {
+                    add_duration(&mut durations.rounded, project, rounding.apply(duration));
+                }
+                _ => (),
+            }
+        }
 
-            previous_chunk_end_time = chunk.end_time;
+        for (project, duration) in day_durations {
+            match config.rounding_for(&project) {
+                Some(rounding) if rounding.scope == RoundingScope::Day => {
+                    add_duration(&mut durations.rounded, &project, rounding.apply(duration));
+                }
+                _ => (),
+            }
         }
     }
 
-    let project_width = max(
-        project_durations.keys().map(String::len).max().unwrap_or(0),
-        "project".len(),
-    );
+    for (project, duration) in &durations.raw {
+        match config.rounding_for(project) {
+            Some(rounding) if rounding.scope == RoundingScope::Report => {
+                durations
+                    .rounded
+                    .insert(project.clone(), rounding.apply(*duration));
+            }
+            _ => (),
+        }
+    }
+
+    durations
+}
 
-    let format_duration = |duration: &chrono::Duration| {
-        let fraction = duration.num_minutes() as f64 / 60.0;
-        let hours = duration.num_hours();
-        let minutes = duration.num_minutes() - hours * 60;
-        format!("{fraction:.2}h ({hours}h {minutes}m)")
+fn add_duration(
+    durations: &mut HashMap<String, chrono::Duration>,
+    key: &str,
+    duration: chrono::Duration,
+) {
+    let entry = durations
+        .entry(key.to_string())
+        .or_insert(chrono::Duration::zero());
+    *entry = *entry + duration;
+}
+
+/// Formats a markdown table with columns as wide as their widest cell.
+fn format_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(column, title)| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain([title.len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_line = |cells: Vec<&str>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!(" {cell:width$} "))
+            .collect();
+        format!("|{}|", cells.join("|"))
     };
 
-    let time_width = max(
-        project_durations
-            .values()
-            .map(|duration| format_duration(duration).len())
-            .max()
-            .unwrap_or(0),
-        "0.00h (0h 0m)".len(),
-    );
+    let mut table = vec![
+        format_line(header.to_vec()),
+        format!(
+            "|{}|",
+            widths
+                .iter()
+                .map(|width| "-".repeat(width + 2))
+                .collect::<Vec<String>>()
+                .join("|")
+        ),
+    ];
+
+    for row in rows {
+        table.push(format_line(row.iter().map(String::as_str).collect()));
+    }
+
+    table.join("\n")
+}
+
+/// Time per project over some days, with breaks and untracked time separately.
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    /// Work per project, with break deductions applied.
+    pub projects: BTreeMap<String, chrono::Duration>,
+    /// Rounded work of the projects that are configured with a rounding.
+    pub rounded: BTreeMap<String, chrono::Duration>,
+    pub breaks: chrono::Duration,
+    pub untracked: chrono::Duration,
+}
 
-    let format_summary_line =
-        |p: &String, d: &String| format!("| {p:project_width$} | {d:time_width$} |");
+impl Summary {
+    /// Returns the total work, i.e. without breaks and untracked time.
+    #[must_use]
+    pub fn work(&self) -> chrono::Duration {
+        self.projects.values().sum()
+    }
+}
+
+/// Computes the summary of the given days.
+///
+/// Expects the chunks for each day to be sorted by end time.
+#[must_use]
+pub fn summarize(days: &[Day], config: &Config) -> Summary {
+    let ProjectDurations {
+        raw: mut projects,
+        rounded,
+        ..
+    } = project_durations(days, config);
+
+    let untracked = projects
+        .remove(UNTRACKED)
+        .unwrap_or(chrono::Duration::zero());
+    let breaks = projects.remove("break").unwrap_or(chrono::Duration::zero());
+
+    Summary {
+        projects: projects.into_iter().collect(),
+        rounded: rounded.into_iter().collect(),
+        breaks,
+        untracked,
+    }
+}
+
+/// Returns the summary of the given days for the HTTP API, in minutes.
+///
+/// Expects the chunks for each day to be sorted by end time.
+#[must_use]
+pub fn summary_json(dates: &[NaiveDate], days: &[Day], config: &Config) -> serde_json::Value {
+    let summary = summarize(days, config);
+
+    json!({
+        "from": dates.first().map(|date| date.format("%Y-%m-%d").to_string()),
+        "to": dates.last().map(|date| date.format("%Y-%m-%d").to_string()),
+        "workMinutes": summary.work().num_minutes(),
+        "breakMinutes": summary.breaks.num_minutes(),
+        "untrackedMinutes": summary.untracked.num_minutes(),
+        "projects": summary
+            .projects
+            .iter()
+            .map(|(project, duration)| json!({
+                "project": project,
+                "minutes": duration.num_minutes(),
+                "roundedMinutes": summary.rounded.get(project).map(chrono::Duration::num_minutes),
+            }))
+            .collect::<Vec<serde_json::Value>>(),
+    })
+}
+
+/// Returns a summary of projects over the given days.
+///
+/// Expects the chunks for each day to be sorted by end time.
+#[must_use]
+pub fn project_summary(days: &[Day], config: &Config, options: &ReportOptions) -> String {
+    let ProjectDurations {
+        raw: mut project_durations,
+        rounded,
+        adjustments,
+    } = project_durations(days, config);
+
+    let untracked_duration = project_durations.remove(UNTRACKED);
 
     let total_duration = project_durations.values().sum::<chrono::Duration>();
 
@@ -138,60 +592,256 @@ fn project_summary(days: &Vec<Day>) -> String {
 
     let duration_without_breaks = project_durations.values().sum::<chrono::Duration>();
 
-    let mut table = vec![
-        format_summary_line(&"project".to_string(), &"time".to_string()),
-        format!(
-            "|{}|{}|",
-            "-".repeat(project_width + 2),
-            "-".repeat(time_width + 2)
-        ),
-    ];
+    let with_rounding = !rounded.is_empty();
+    let rounded_or_raw: HashMap<String, chrono::Duration> = project_durations
+        .iter()
+        .map(|(project, duration)| (project.clone(), *rounded.get(project).unwrap_or(duration)))
+        .collect();
+
+    let rolled_up_durations = roll_up(&project_durations);
+    let rolled_up_rounded = roll_up(&rounded_or_raw);
 
-    let mut sorted_projects: Vec<String> = project_durations.keys().cloned().collect();
+    let mut rows = vec![];
+
+    let mut sorted_projects: Vec<(&String, &chrono::Duration)> = rolled_up_durations
+        .iter()
+        .filter(|(project, _)| {
+            options
+                .depth
+                .is_none_or(|depth| project_depth(project) <= depth)
+        })
+        .collect();
     sorted_projects.sort();
-    for project in sorted_projects {
-        table.push(format_summary_line(
-            &project,
-            &format_duration(
-                project_durations
-                    .get(&project)
-                    .expect("project will not be empty, because it's definitely a key in the map"),
-            ),
-        ));
+    for (project, duration) in sorted_projects {
+        let mut row = vec![project.clone(), format_duration(duration)];
+        if with_rounding {
+            row.push(format_duration(&rolled_up_rounded[project]));
+        }
+        rows.push(row);
     }
 
-    table.push(format_summary_line(
-        &"break".to_string(),
-        &format_duration(&break_duration),
-    ));
+    let mut other_rows = vec![("break", break_duration)];
+
+    let mut untracked_line = String::new();
+    if let Some(untracked_duration) = untracked_duration {
+        other_rows.push((UNTRACKED, untracked_duration));
+        untracked_line = format!("\n- untracked: {}", format_duration(&untracked_duration));
+    }
+
+    for (project, duration) in other_rows {
+        let mut row = vec![project.to_string(), format_duration(&duration)];
+        if with_rounding {
+            row.push(String::new());
+        }
+        rows.push(row);
+    }
+
+    let mut rounded_line = String::new();
+    let mut header = vec!["project", "time"];
+    if with_rounding {
+        header.push("rounded");
+
+        let rounded_without_breaks = rounded_or_raw.values().sum::<chrono::Duration>();
+        rounded_line = format!(
+            "\n- without breaks, rounded: {}",
+            format_duration(&rounded_without_breaks)
+        );
+    }
+
+    let table = format_table(&header, &rows);
+
+    let adjustments = if adjustments.is_empty() {
+        String::new()
+    } else {
+        format!("\n\n## adjustments\n\n{}", adjustments.join("\n"))
+    };
+
+    let billing_lines = billing_lines(&project_durations, &rounded, config);
+    let billing = if billing_lines.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\n\n## billing\n\n{}\n\n{}",
+            format_table(
+                &["project", "billable hours", "rate", "amount"],
+                &billing_lines
+                    .iter()
+                    .map(|line| {
+                        vec![
+                            line.project.clone(),
+                            format!("{:.2}h", line.hours),
+                            line.format_rate(),
+                            line.format_amount(),
+                        ]
+                    })
+                    .collect::<Vec<Vec<String>>>()
+            ),
+            format_billing_totals(&billing_lines)
+        )
+    };
 
-    let table = table.join("\n");
     format!(
         "## summary
 
 - total amount of work: {total}
-- without breaks: {without_breaks}
+- without breaks: {without_breaks}{rounded_line}{untracked_line}
 
-{table}",
+{table}{adjustments}{billing}",
         total = format_duration(&total_duration),
         without_breaks = format_duration(&duration_without_breaks)
     )
 }
 
+/// Adds the time of sub-projects like `kyoshi/backend` to their parents like `kyoshi`.
+///
+/// Parents that were not tracked themselves are added as well.
+fn roll_up(durations: &HashMap<String, chrono::Duration>) -> HashMap<String, chrono::Duration> {
+    let mut rolled_up = HashMap::new();
+
+    for (project, duration) in durations {
+        let segments: Vec<&str> = project.split('/').collect();
+        for depth in 1..=segments.len() {
+            add_duration(&mut rolled_up, &segments[..depth].join("/"), *duration);
+        }
+    }
+
+    rolled_up
+}
+
+fn project_depth(project: &str) -> usize {
+    project.split('/').count()
+}
+
+/// Billable time of a project and the resulting amount.
+struct BillingLine {
+    project: String,
+    hours: f64,
+    rate: Option<f64>,
+    currency: String,
+}
+
+impl BillingLine {
+    fn amount(&self) -> Option<f64> {
+        self.rate.map(|rate| rate * self.hours)
+    }
+
+    fn format_rate(&self) -> String {
+        self.rate
+            .map_or("-".to_string(), |rate| format_money(rate, &self.currency))
+    }
+
+    fn format_amount(&self) -> String {
+        self.amount().map_or("-".to_string(), |amount| {
+            format_money(amount, &self.currency)
+        })
+    }
+}
+
+fn format_money(amount: f64, currency: &str) -> String {
+    format!("{amount:.2} {currency}").trim_end().to_string()
+}
+
+/// Returns the billing lines of all billable projects, using the rounded time if available.
+///
+/// The time of sub-projects is billed to their nearest billable parent.
+#[allow(clippy::cast_precision_loss)]
+fn billing_lines(
+    raw: &HashMap<String, chrono::Duration>,
+    rounded: &HashMap<String, chrono::Duration>,
+    config: &Config,
+) -> Vec<BillingLine> {
+    let mut billed: HashMap<String, chrono::Duration> = HashMap::new();
+    for (project, duration) in raw {
+        if let Some(billing_project) = config.billing_project(project) {
+            let duration = rounded.get(project).unwrap_or(duration);
+            add_duration(&mut billed, &billing_project.name, *duration);
+        }
+    }
+
+    let mut lines: Vec<BillingLine> = billed
+        .into_iter()
+        .filter_map(|(name, duration)| {
+            let project = config.project(&name)?;
+            Some(BillingLine {
+                project: name,
+                hours: duration.num_minutes() as f64 / 60.0,
+                rate: project.rate,
+                currency: project.currency.clone().unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    lines.sort_by(|a, b| a.project.cmp(&b.project));
+    lines
+}
+
+/// Returns the total amount per currency.
+fn format_billing_totals(lines: &[BillingLine]) -> String {
+    let mut totals: BTreeMap<&str, f64> = BTreeMap::new();
+    for line in lines {
+        if let Some(amount) = line.amount() {
+            *totals.entry(&line.currency).or_insert(0.0) += amount;
+        }
+    }
+
+    totals
+        .iter()
+        .map(|(currency, total)| format!("- total: {}", format_money(*total, currency)))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Returns an invoice with one line item per billable project.
+///
+/// Expects the chunks for each day to be sorted by end time.
+fn format_invoice(title: &str, days: &[Day], config: &Config) -> String {
+    let durations = project_durations(days, config);
+    let lines = billing_lines(&durations.raw, &durations.rounded, config);
+
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            vec![
+                (index + 1).to_string(),
+                line.project.clone(),
+                format!("{:.2}h", line.hours),
+                line.format_rate(),
+                line.format_amount(),
+            ]
+        })
+        .collect();
+
+    format!(
+        "# Invoice for: {title}
+
+{}
+
+{}",
+        format_table(
+            &["item", "description", "quantity", "unit price", "amount"],
+            &rows
+        ),
+        format_billing_totals(&lines)
+    )
+}
+
 fn detail_table(day: &Day) -> String {
+    let spans = day_spans(day);
+
     let project_width = max(
-        day.chunks
+        spans
             .iter()
-            .map(|chunk| chunk.project.len())
+            .map(|span| span.project.len())
             .max()
             .unwrap_or(0),
         "check-in".len(),
     );
 
     let description_width = max(
-        day.chunks
+        spans
             .iter()
-            .map(|chunk| chunk.description.as_ref().map_or(0, String::len))
+            .map(|span| span.description.map_or(0, String::len))
             .max()
             .unwrap_or(0),
         "description".len(),
@@ -222,11 +872,11 @@ fn detail_table(day: &Day) -> String {
         ),
     ];
 
-    for chunk in &day.chunks {
+    for span in spans {
         table.push(format_detail_line(
-            &chunk.end_time.format("- %H:%M").to_string(),
-            &chunk.project,
-            chunk.description.as_ref().unwrap_or(&String::new()),
+            &span.end.format("- %H:%M").to_string(),
+            &span.project.to_string(),
+            span.description.unwrap_or(&String::new()),
         ));
     }
 
@@ -244,7 +894,11 @@ fn detail_table(day: &Day) -> String {
 mod tests {
     use super::*;
     use crate::commands::{Chunk, Day};
-    use chrono::{NaiveDate, NaiveTime};
+    use crate::config::Project;
+    use crate::rounding::{Rounding, RoundingMode};
+    use crate::rules::{BreakDeduction, Rule};
+    use crate::test_util::{chunk_until, day, time};
+    use chrono::NaiveDate;
 
     #[cfg(test)]
     use pretty_assertions::assert_eq;
@@ -297,48 +951,58 @@ mod tests {
                     project: "kyoshi".to_string(),
                     description: Some("answer messages from colleagues".to_string()),
                     end_time: NaiveTime::from_hms_opt(8, 45, 0).unwrap(),
+                    ..Chunk::default()
                 },
                 Chunk {
                     project: "break".to_string(),
                     description: Some("coffee break".to_string()),
                     end_time: NaiveTime::from_hms_opt(9, 15, 0).unwrap(),
+                    ..Chunk::default()
                 },
                 Chunk {
                     project: "kyoshi".to_string(),
                     description: Some("develop feature #123".to_string()),
                     end_time: NaiveTime::from_hms_opt(11, 23, 0).unwrap(),
+                    ..Chunk::default()
                 },
                 Chunk {
                     project: "kyoshi".to_string(),
                     description: None,
                     end_time: NaiveTime::from_hms_opt(11, 55, 0).unwrap(),
+                    ..Chunk::default()
                 },
                 Chunk {
                     project: "break".to_string(),
                     description: Some("lunch break".to_string()),
                     end_time: NaiveTime::from_hms_opt(12, 43, 0).unwrap(),
+                    ..Chunk::default()
                 },
                 Chunk {
                     project: "lake laogai".to_string(),
                     description: Some("answer emails".to_string()),
                     end_time: NaiveTime::from_hms_opt(13, 00, 0).unwrap(),
+                    ..Chunk::default()
                 },
                 Chunk {
                     project: "korra".to_string(),
                     description: Some("daily scrum".to_string()),
                     end_time: NaiveTime::from_hms_opt(9, 00, 0).unwrap(),
+                    ..Chunk::default()
                 },
                 Chunk {
                     project: "korra".to_string(),
                     description: Some("refinement meeting".to_string()),
                     end_time: NaiveTime::from_hms_opt(14, 00, 0).unwrap(),
+                    ..Chunk::default()
                 },
                 Chunk {
                     project: "kyoshi".to_string(),
                     description: Some("develop feature #123".to_string()),
                     end_time: NaiveTime::from_hms_opt(16, 34, 0).unwrap(),
+                    ..Chunk::default()
                 },
             ],
+            ..Day::default()
         };
 
         let expected = "\
@@ -371,6 +1035,405 @@ mod tests {
 | - 14:00 | korra       | refinement meeting              |
 | - 16:34 | kyoshi      | develop feature #123            |";
 
-        assert_eq!(expected, format_day(day));
+        assert_eq!(
+            expected,
+            format_day(day, &Config::default(), &ReportOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_format_day_with_gaps() {
+        let day = day(vec![
+            Chunk {
+                project: "kyoshi".to_string(),
+                description: Some("code review".to_string()),
+                end_time: time(9, 0),
+                ..Chunk::default()
+            },
+            Chunk {
+                project: "korra".to_string(),
+                description: Some("daily scrum".to_string()),
+                start_time: Some(time(9, 30)),
+                end_time: time(9, 45),
+                ..Chunk::default()
+            },
+            Chunk {
+                project: "kyoshi".to_string(),
+                end_time: time(11, 0),
+                ..Chunk::default()
+            },
+        ]);
+
+        let expected = "\
+# Log for: 2023-11-20
+
+## summary
+
+- total amount of work: 2.50h (2h 30m)
+- without breaks: 2.50h (2h 30m)
+- untracked: 0.50h (0h 30m)
+
+| project   | time           |
+|-----------|----------------|
+| korra     | 0.25h (0h 15m) |
+| kyoshi    | 2.25h (2h 15m) |
+| break     | 0.00h (0h 0m)  |
+| untracked | 0.50h (0h 30m) |
+
+## details
+
+| time    | project   | description |
+|---------|-----------|-------------|
+| 08:00   | check-in  |             |
+| - 09:00 | kyoshi    | code review |
+| - 09:30 | untracked |             |
+| - 09:45 | korra     | daily scrum |
+| - 11:00 | kyoshi    |             |";
+
+        assert_eq!(
+            expected,
+            format_day(day, &Config::default(), &ReportOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_project_summary_with_break_deduction() {
+        let day = day(vec![
+            Chunk {
+                project: "kyoshi".to_string(),
+                end_time: time(12, 0),
+                ..Chunk::default()
+            },
+            Chunk {
+                project: "korra".to_string(),
+                end_time: time(15, 0),
+                ..Chunk::default()
+            },
+        ]);
+
+        let config = Config {
+            rules: vec![Rule::MinBreak {
+                after_hours: 6.0,
+                break_minutes: 30,
+                min_block_minutes: 0,
+            }],
+            break_deduction: Some(BreakDeduction::LargestChunk),
+            ..Config::default()
+        };
+
+        let expected = "\
+## summary
+
+- total amount of work: 7.00h (7h 0m)
+- without breaks: 6.50h (6h 30m)
+
+| project | time           |
+|---------|----------------|
+| korra   | 3.00h (3h 0m)  |
+| kyoshi  | 3.50h (3h 30m) |
+| break   | 0.50h (0h 30m) |
+
+## adjustments
+
+- 2023-11-20 11:30: 0.50h (0h 30m) of kyoshi counted as break (minimum break of 0.50h (0h 30m) after 6.00h (6h 0m) of work)";
+
+        assert_eq!(
+            expected,
+            project_summary(&[day], &config, &ReportOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_project_summary_with_rounding() {
+        let day = day(vec![
+            chunk_until("kyoshi", 8, 10),
+            chunk_until("korra", 8, 20),
+            chunk_until("kyoshi", 8, 50),
+            chunk_until("korra", 9, 0),
+            chunk_until("lake laogai", 9, 7),
+        ]);
+
+        let rounding = |scope| Rounding {
+            minutes: 15,
+            mode: RoundingMode::Up,
+            scope,
+        };
+        let config = Config {
+            projects: vec![
+                Project {
+                    name: "kyoshi".to_string(),
+                    rounding: Some(rounding(RoundingScope::Chunk)),
+                    ..Project::default()
+                },
+                Project {
+                    name: "korra".to_string(),
+                    rounding: Some(rounding(RoundingScope::Day)),
+                    ..Project::default()
+                },
+                Project::new("lake laogai".to_string()),
+            ],
+            ..Config::default()
+        };
+
+        let expected = "\
+## summary
+
+- total amount of work: 1.12h (1h 7m)
+- without breaks: 1.12h (1h 7m)
+- without breaks, rounded: 1.37h (1h 22m)
+
+| project     | time           | rounded        |
+|-------------|----------------|----------------|
+| korra       | 0.33h (0h 20m) | 0.50h (0h 30m) |
+| kyoshi      | 0.67h (0h 40m) | 0.75h (0h 45m) |
+| lake laogai | 0.12h (0h 7m)  | 0.12h (0h 7m)  |
+| break       | 0.00h (0h 0m)  |                |";
+
+        assert_eq!(
+            expected,
+            project_summary(&[day], &config, &ReportOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_format_invoice() {
+        let day = day(vec![
+            chunk_until("kyoshi", 10, 0),
+            chunk_until("korra", 11, 0),
+            chunk_until("lake laogai", 14, 0),
+            chunk_until("break", 15, 0),
+            chunk_until("omashu", 16, 0),
+        ]);
+
+        let billable = |name: &str, rate, currency: &str| Project {
+            name: name.to_string(),
+            billable: true,
+            rate,
+            currency: Some(currency.to_string()),
+            ..Project::default()
+        };
+        let config = Config {
+            projects: vec![
+                billable("kyoshi", Some(80.0), "EUR"),
+                billable("korra", Some(95.5), "EUR"),
+                billable("omashu", Some(100.0), "USD"),
+                billable("lake laogai", None, "EUR"),
+            ],
+            ..Config::default()
+        };
+
+        let expected = "\
+# Invoice for: November of 2023
+
+| item | description | quantity | unit price | amount     |
+|------|-------------|----------|------------|------------|
+| 1    | korra       | 1.00h    | 95.50 EUR  | 95.50 EUR  |
+| 2    | kyoshi      | 2.00h    | 80.00 EUR  | 160.00 EUR |
+| 3    | lake laogai | 3.00h    | -          | -          |
+| 4    | omashu      | 1.00h    | 100.00 USD | 100.00 USD |
+
+- total: 255.50 EUR
+- total: 100.00 USD";
+
+        assert_eq!(
+            expected,
+            format_invoice("November of 2023", &[day], &config)
+        );
+    }
+
+    #[test]
+    fn test_format_invoice_with_sub_projects() {
+        let chunk = crate::test_util::chunk;
+
+        let day = day(vec![
+            chunk("kyoshi/backend", time(9, 5)),
+            chunk("kyoshi", time(10, 0)),
+            chunk("kyoshi/frontend", time(10, 50)),
+            chunk("kyoshi/frontend/design", time(11, 0)),
+        ]);
+
+        let config = Config {
+            projects: vec![
+                Project {
+                    name: "kyoshi".to_string(),
+                    billable: true,
+                    rate: Some(80.0),
+                    currency: Some("EUR".to_string()),
+                    rounding: Some(Rounding {
+                        minutes: 30,
+                        mode: RoundingMode::Up,
+                        scope: RoundingScope::Chunk,
+                    }),
+                    ..Project::default()
+                },
+                Project {
+                    name: "kyoshi/frontend".to_string(),
+                    billable: true,
+                    rate: Some(100.0),
+                    currency: Some("EUR".to_string()),
+                    ..Project::default()
+                },
+            ],
+            ..Config::default()
+        };
+
+        // every chunk is rounded up to 30 minutes with the rounding of kyoshi,
+        // the design is billed with the rate of the frontend
+        let expected = "\
+# Invoice for: November of 2023
+
+| item | description     | quantity | unit price | amount     |
+|------|-----------------|----------|------------|------------|
+| 1    | kyoshi          | 2.50h    | 80.00 EUR  | 200.00 EUR |
+| 2    | kyoshi/frontend | 1.50h    | 100.00 EUR | 150.00 EUR |
+
+- total: 350.00 EUR";
+
+        assert_eq!(
+            expected,
+            format_invoice("November of 2023", &[day], &config)
+        );
+    }
+
+    #[test]
+    fn test_tag_summary() {
+        let chunk = |project: &str, tags: &[&str], hour| Chunk {
+            tags: tags.iter().map(std::string::ToString::to_string).collect(),
+            ..chunk_until(project, hour, 0)
+        };
+
+        let day = day(vec![
+            chunk("kyoshi", &["meeting"], 9),
+            chunk("korra", &["meeting", "review"], 11),
+            chunk("break", &[], 12),
+            chunk("kyoshi", &[], 15),
+        ]);
+
+        let expected = "\
+## tags
+
+| tag      | time          |
+|----------|---------------|
+| meeting  | 3.00h (3h 0m) |
+| review   | 2.00h (2h 0m) |
+| untagged | 3.00h (3h 0m) |";
+
+        assert_eq!(expected, tag_summary(&[day]));
+    }
+
+    #[test]
+    fn test_ticket_summary() {
+        let chunk = |project: &str, tickets: &[&str], hour| Chunk {
+            tickets: tickets
+                .iter()
+                .map(std::string::ToString::to_string)
+                .collect(),
+            ..chunk_until(project, hour, 0)
+        };
+
+        let day = day(vec![
+            chunk("kyoshi", &["#123"], 9),
+            chunk("korra", &["ABC-7", "#123"], 11),
+            chunk("break", &[], 12),
+            chunk("kyoshi", &[], 13),
+        ]);
+
+        let expected = "\
+## tickets
+
+| ticket    | time          |
+|-----------|---------------|
+| #123      | 3.00h (3h 0m) |
+| ABC-7     | 2.00h (2h 0m) |
+| no ticket | 1.00h (1h 0m) |";
+
+        assert_eq!(expected, ticket_summary(&[day]));
+    }
+
+    #[test]
+    fn test_project_summary_with_sub_projects() {
+        let day = day(vec![
+            chunk_until("kyoshi/backend", 10, 0),
+            chunk_until("kyoshi/frontend/design", 11, 0),
+            chunk_until("kyoshi", 12, 0),
+            chunk_until("korra", 13, 0),
+        ]);
+
+        let expected = "\
+## summary
+
+- total amount of work: 5.00h (5h 0m)
+- without breaks: 5.00h (5h 0m)
+
+| project                | time          |
+|------------------------|---------------|
+| korra                  | 1.00h (1h 0m) |
+| kyoshi                 | 4.00h (4h 0m) |
+| kyoshi/backend         | 2.00h (2h 0m) |
+| kyoshi/frontend        | 1.00h (1h 0m) |
+| kyoshi/frontend/design | 1.00h (1h 0m) |
+| break                  | 0.00h (0h 0m) |";
+
+        assert_eq!(
+            expected,
+            project_summary(
+                std::slice::from_ref(&day),
+                &Config::default(),
+                &ReportOptions::default()
+            )
+        );
+
+        let expected = "\
+## summary
+
+- total amount of work: 5.00h (5h 0m)
+- without breaks: 5.00h (5h 0m)
+
+| project         | time          |
+|-----------------|---------------|
+| korra           | 1.00h (1h 0m) |
+| kyoshi          | 4.00h (4h 0m) |
+| kyoshi/backend  | 2.00h (2h 0m) |
+| kyoshi/frontend | 1.00h (1h 0m) |
+| break           | 0.00h (0h 0m) |";
+
+        let options = ReportOptions {
+            depth: Some(2),
+            ..ReportOptions::default()
+        };
+        assert_eq!(
+            expected,
+            project_summary(&[day], &Config::default(), &options)
+        );
+    }
+
+    #[test]
+    fn test_summarize() {
+        let chunk = |project: &str, start: Option<u32>, hour| Chunk {
+            start_time: start.map(|start| time(start, 0)),
+            ..chunk_until(project, hour, 0)
+        };
+
+        let day = day(vec![
+            chunk("kyoshi", None, 10),
+            chunk("break", None, 11),
+            chunk("korra", Some(12), 13),
+        ]);
+
+        let summary = summarize(&[day], &Config::default());
+
+        assert_eq!(
+            summary,
+            Summary {
+                projects: BTreeMap::from([
+                    ("korra".to_string(), chrono::Duration::hours(1)),
+                    ("kyoshi".to_string(), chrono::Duration::hours(2)),
+                ]),
+                rounded: BTreeMap::new(),
+                breaks: chrono::Duration::hours(1),
+                untracked: chrono::Duration::hours(1),
+            }
+        );
+        assert_eq!(summary.work(), chrono::Duration::hours(3));
     }
 }
@@ -0,0 +1,93 @@
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Rounds the time of a project to fixed increments, e.g. for billing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rounding {
+    /// Size of the increments, e.g. 15 or 6.
+    pub minutes: i64,
+    #[serde(default)]
+    pub mode: RoundingMode,
+    #[serde(default)]
+    pub scope: RoundingScope,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RoundingMode {
+    #[default]
+    Up,
+    Down,
+    Nearest,
+}
+
+/// The amount of time that is rounded at once.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RoundingScope {
+    #[default]
+    Chunk,
+    Day,
+    Report,
+}
+
+impl Rounding {
+    #[must_use]
+    pub fn apply(&self, duration: Duration) -> Duration {
+        let increment = self.minutes * 60;
+        if increment <= 0 {
+            return duration;
+        }
+
+        let seconds = duration.num_seconds();
+        let remainder = seconds.rem_euclid(increment);
+        if remainder == 0 {
+            return duration;
+        }
+
+        let round_up = match self.mode {
+            RoundingMode::Up => true,
+            RoundingMode::Down => false,
+            RoundingMode::Nearest => remainder * 2 >= increment,
+        };
+
+        if round_up {
+            Duration::seconds(seconds - remainder + increment)
+        } else {
+            Duration::seconds(seconds - remainder)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let rounding = |minutes, mode| Rounding {
+            minutes,
+            mode,
+            scope: RoundingScope::Chunk,
+        };
+
+        let up = rounding(15, RoundingMode::Up);
+        assert_eq!(up.apply(Duration::minutes(0)), Duration::minutes(0));
+        assert_eq!(up.apply(Duration::minutes(1)), Duration::minutes(15));
+        assert_eq!(up.apply(Duration::minutes(15)), Duration::minutes(15));
+        assert_eq!(up.apply(Duration::minutes(16)), Duration::minutes(30));
+
+        let down = rounding(15, RoundingMode::Down);
+        assert_eq!(down.apply(Duration::minutes(14)), Duration::minutes(0));
+        assert_eq!(down.apply(Duration::minutes(29)), Duration::minutes(15));
+
+        let nearest = rounding(6, RoundingMode::Nearest);
+        assert_eq!(nearest.apply(Duration::minutes(2)), Duration::minutes(0));
+        assert_eq!(nearest.apply(Duration::minutes(3)), Duration::minutes(6));
+        assert_eq!(nearest.apply(Duration::minutes(40)), Duration::minutes(42));
+
+        let invalid = rounding(0, RoundingMode::Up);
+        assert_eq!(invalid.apply(Duration::minutes(7)), Duration::minutes(7));
+    }
+}
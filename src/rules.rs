@@ -0,0 +1,376 @@
+use crate::commands::Day;
+use crate::config::hours_to_duration;
+use crate::reporting::{break_durations, day_spans, format_duration, is_work, work_duration};
+use chrono::{Duration, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::cmp::{max, min, Reverse};
+
+/// A rule about working times that each day is checked against, e.g. from labor law.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Rule {
+    /// Requires a minimum amount of break once the work exceeds a threshold.
+    #[serde(rename_all = "camelCase")]
+    MinBreak {
+        after_hours: f64,
+        break_minutes: i64,
+        /// Breaks shorter than this are not counted.
+        #[serde(default)]
+        min_block_minutes: i64,
+    },
+    /// Limits the work per day.
+    #[serde(rename_all = "camelCase")]
+    MaxWork { hours: f64 },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Violation {
+    MissingBreak {
+        worked: Duration,
+        required: Duration,
+        taken: Duration,
+    },
+    TooMuchWork {
+        worked: Duration,
+        max: Duration,
+    },
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::MissingBreak {
+                worked,
+                required,
+                taken,
+            } => write!(
+                f,
+                "{} of work require a break of {}, but only {} were taken",
+                format_duration(worked),
+                format_duration(required),
+                format_duration(taken)
+            ),
+            Violation::TooMuchWork { worked, max } => write!(
+                f,
+                "{} of work exceed the maximum of {}",
+                format_duration(worked),
+                format_duration(max)
+            ),
+        }
+    }
+}
+
+/// Checks a day against the rules.
+///
+/// Of several violated break rules, only the one requiring the longest break is reported.
+#[must_use]
+pub fn check_day(day: &Day, rules: &[Rule]) -> Vec<Violation> {
+    let mut day = day.clone();
+    day.chunks.sort_by_key(|chunk| chunk.end_time);
+
+    let worked = work_duration(&day);
+
+    let mut violations: Vec<Violation> = strictest_missing_break(&day, rules)
+        .map(|missing| Violation::MissingBreak {
+            worked,
+            required: missing.required,
+            taken: missing.taken,
+        })
+        .into_iter()
+        .collect();
+
+    for rule in rules {
+        if let Rule::MaxWork { hours } = rule {
+            let max = hours_to_duration(*hours);
+            if worked > max {
+                violations.push(Violation::TooMuchWork { worked, max });
+            }
+        }
+    }
+
+    violations
+}
+
+/// A violated `MinBreak` rule.
+struct MissingBreak {
+    after: Duration,
+    required: Duration,
+    taken: Duration,
+}
+
+/// Returns the violated break rule requiring the longest break, if any.
+///
+/// Expects the chunks to be sorted by end time.
+fn strictest_missing_break(day: &Day, rules: &[Rule]) -> Option<MissingBreak> {
+    let worked = work_duration(day);
+    let breaks = break_durations(day);
+
+    rules
+        .iter()
+        .filter_map(|rule| match rule {
+            Rule::MinBreak {
+                after_hours,
+                break_minutes,
+                min_block_minutes,
+            } => {
+                let missing = MissingBreak {
+                    after: hours_to_duration(*after_hours),
+                    required: Duration::minutes(*break_minutes),
+                    taken: breaks
+                        .iter()
+                        .filter(|duration| **duration >= Duration::minutes(*min_block_minutes))
+                        .sum::<Duration>(),
+                };
+
+                (worked > missing.after && missing.taken < missing.required).then_some(missing)
+            }
+            Rule::MaxWork { .. } => None,
+        })
+        .max_by_key(|missing| missing.required)
+}
+
+/// How a missing break is deducted from the work in reports.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BreakDeduction {
+    /// Deducts the missing break from the largest work chunk of the day.
+    LargestChunk,
+    /// Inserts a virtual break at the point where the work exceeds the threshold of the rule.
+    VirtualBreak,
+}
+
+/// Work time of a chunk that is counted as break in reports.
+#[derive(Debug, PartialEq)]
+pub struct Adjustment {
+    pub project: String,
+    pub start: NaiveTime,
+    pub duration: Duration,
+    pub reason: String,
+}
+
+/// Returns the adjustments needed to fulfill the strictest violated break rule.
+///
+/// Expects the chunks to be sorted by end time.
+#[must_use]
+pub fn break_adjustments(day: &Day, rules: &[Rule], policy: BreakDeduction) -> Vec<Adjustment> {
+    let Some(missing_break) = strictest_missing_break(day, rules) else {
+        return vec![];
+    };
+
+    let reason = format!(
+        "minimum break of {} after {} of work",
+        format_duration(&missing_break.required),
+        format_duration(&missing_break.after)
+    );
+    let mut missing = missing_break.required - missing_break.taken;
+
+    let spans = day_spans(day);
+    let work_spans = spans.iter().filter(|span| is_work(span.project));
+
+    match policy {
+        BreakDeduction::LargestChunk => {
+            // a single chunk might be shorter than the missing break,
+            // the rest is taken from the next largest ones
+            let mut work_spans: Vec<_> = work_spans.collect();
+            work_spans.sort_by_key(|span| Reverse(span.end - span.start));
+
+            let mut adjustments = vec![];
+            for span in work_spans {
+                if missing <= Duration::zero() {
+                    break;
+                }
+
+                let duration = min(missing, span.end - span.start);
+                adjustments.push(Adjustment {
+                    project: span.project.to_string(),
+                    start: span.end - duration,
+                    duration,
+                    reason: reason.clone(),
+                });
+                missing = missing - duration;
+            }
+
+            adjustments
+        }
+        BreakDeduction::VirtualBreak => {
+            let mut adjustments = vec![];
+            let mut worked = Duration::zero();
+
+            for span in work_spans {
+                let span_duration = span.end - span.start;
+
+                if missing > Duration::zero() && worked + span_duration > missing_break.after {
+                    // the break starts where the threshold is reached, or at the start of
+                    // the following chunks if it didn't fit into the first one
+                    let start = span.start + max(missing_break.after - worked, Duration::zero());
+                    let duration = min(missing, span.end - start);
+
+                    adjustments.push(Adjustment {
+                        project: span.project.to_string(),
+                        start,
+                        duration,
+                        reason: reason.clone(),
+                    });
+                    missing = missing - duration;
+                }
+
+                worked = worked + span_duration;
+            }
+
+            adjustments
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_util::{chunk_until, day, time};
+
+    fn german_rules() -> Vec<Rule> {
+        vec![
+            Rule::MinBreak {
+                after_hours: 6.0,
+                break_minutes: 30,
+                min_block_minutes: 15,
+            },
+            Rule::MinBreak {
+                after_hours: 9.0,
+                break_minutes: 45,
+                min_block_minutes: 15,
+            },
+            Rule::MaxWork { hours: 10.0 },
+        ]
+    }
+
+    #[test]
+    fn test_check_day() {
+        let mut day = day(vec![
+            chunk_until("kyoshi", 12, 0),
+            chunk_until("break", 12, 10),
+            chunk_until("kyoshi", 14, 20),
+        ]);
+
+        // a break of 10 minutes doesn't count
+        assert_eq!(
+            check_day(&day, &german_rules()),
+            vec![Violation::MissingBreak {
+                worked: Duration::minutes(6 * 60 + 10),
+                required: Duration::minutes(30),
+                taken: Duration::zero(),
+            }]
+        );
+
+        day.chunks.push(chunk_until("break", 14, 50));
+        day.chunks.push(chunk_until("kyoshi", 18, 0));
+        day.chunks.push(chunk_until("korra", 19, 30));
+
+        assert_eq!(
+            check_day(&day, &german_rules()),
+            vec![
+                Violation::MissingBreak {
+                    worked: Duration::minutes(10 * 60 + 50),
+                    required: Duration::minutes(45),
+                    taken: Duration::minutes(30),
+                },
+                Violation::TooMuchWork {
+                    worked: Duration::minutes(10 * 60 + 50),
+                    max: Duration::hours(10),
+                }
+            ]
+        );
+
+        assert_eq!(
+            "10.83h (10h 50m) of work exceed the maximum of 10.00h (10h 0m)",
+            check_day(&day, &german_rules())[1].to_string()
+        );
+    }
+
+    #[test]
+    fn test_break_adjustments() {
+        let day = day(vec![
+            chunk_until("korra", 9, 0),
+            chunk_until("kyoshi", 13, 50),
+            chunk_until("break", 14, 0),
+            chunk_until("korra", 16, 0),
+        ]);
+        let rules = vec![Rule::MinBreak {
+            after_hours: 6.0,
+            break_minutes: 30,
+            min_block_minutes: 0,
+        }];
+
+        assert_eq!(
+            break_adjustments(&day, &rules, BreakDeduction::LargestChunk),
+            vec![Adjustment {
+                project: "kyoshi".to_string(),
+                start: time(13, 30),
+                duration: Duration::minutes(20),
+                reason: "minimum break of 0.50h (0h 30m) after 6.00h (6h 0m) of work".to_string(),
+            }]
+        );
+
+        // the threshold of 6h is reached at 14:10, in the last chunk
+        assert_eq!(
+            break_adjustments(&day, &rules, BreakDeduction::VirtualBreak),
+            vec![Adjustment {
+                project: "korra".to_string(),
+                start: time(14, 10),
+                duration: Duration::minutes(20),
+                reason: "minimum break of 0.50h (0h 30m) after 6.00h (6h 0m) of work".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_break_adjustments_largest_chunk_too_short() {
+        // 6h 30m of work in chunks of 10m, without any break
+        let chunks = (1..=39)
+            .map(|index| chunk_until("kyoshi", 8 + index * 10 / 60, index * 10 % 60))
+            .collect();
+        let day = day(chunks);
+        let rules = vec![Rule::MinBreak {
+            after_hours: 6.0,
+            break_minutes: 30,
+            min_block_minutes: 0,
+        }];
+
+        let adjustments = break_adjustments(&day, &rules, BreakDeduction::LargestChunk);
+
+        assert_eq!(
+            adjustments
+                .iter()
+                .map(|adjustment| (adjustment.start, adjustment.duration))
+                .collect::<Vec<_>>(),
+            vec![
+                (time(8, 0), Duration::minutes(10)),
+                (time(8, 10), Duration::minutes(10)),
+                (time(8, 20), Duration::minutes(10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rules_from_json() {
+        let rules: Vec<Rule> = serde_json::from_str(
+            r#"[
+                {"type": "minBreak", "afterHours": 6, "breakMinutes": 30},
+                {"type": "maxWork", "hours": 10}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules,
+            vec![
+                Rule::MinBreak {
+                    after_hours: 6.0,
+                    break_minutes: 30,
+                    min_block_minutes: 0,
+                },
+                Rule::MaxWork { hours: 10.0 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use crate::commands::{
+    check_project_configured, load_tracking_day, time_format, track_chunks, Chunk,
+    CommandExecutionError, Day,
+};
+use crate::config;
+use crate::rules::Violation;
+use crate::suggest::untracked_gaps;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// A chunk that happens every week on the same days, like a daily stand-up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringChunk {
+    /// Days like `"Mon"` or `"friday"` the chunk happens on, every day if empty.
+    #[serde(
+        default,
+        with = "weekdays_format",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub weekdays: Vec<Weekday>,
+
+    #[serde(with = "time_format")]
+    pub start: NaiveTime,
+
+    #[serde(with = "time_format")]
+    pub end: NaiveTime,
+
+    pub project: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl RecurringChunk {
+    fn happens_on(&self, date: NaiveDate) -> bool {
+        self.weekdays.is_empty() || self.weekdays.contains(&date.weekday())
+    }
+}
+
+/// Why a recurring chunk is not tracked.
+#[derive(Debug, PartialEq)]
+pub enum Skipped {
+    NotOver,
+    Conflict,
+}
+
+impl std::fmt::Display for Skipped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Skipped::NotOver => write!(f, "it is not over yet"),
+            Skipped::Conflict => write!(f, "it overlaps tracked time or the check-in"),
+        }
+    }
+}
+
+/// The recurring chunks of a date, each tracked or skipped.
+#[derive(Debug, Default)]
+pub struct AppliedSchedule {
+    pub chunks: Vec<(RecurringChunk, Result<(), Skipped>)>,
+    /// Rules the day violates after tracking.
+    pub warnings: Vec<Violation>,
+}
+
+/// Tracks the recurring chunks of the date, as far as they are over and do not overlap
+/// the chunks tracked already.
+///
+/// # Errors
+///
+/// Fails if the date is in the future or was not checked in, a recurring chunk of the
+/// date is not valid, or if the day or the config cannot be loaded or saved.
+pub fn apply_schedule(
+    date: NaiveDate,
+    now: NaiveDateTime,
+) -> Result<AppliedSchedule, CommandExecutionError> {
+    if date > now.date() {
+        return Err(CommandExecutionError::NoTrackingAfterCurrentTime);
+    }
+
+    let mut day = load_tracking_day(date)?;
+    let config = config::load_config()?;
+
+    let recurring: Vec<&RecurringChunk> = config
+        .schedule
+        .iter()
+        .filter(|recurring| recurring.happens_on(date))
+        .collect();
+    if recurring.is_empty() {
+        return Ok(AppliedSchedule::default());
+    }
+    for recurring in &recurring {
+        check_project_configured(&config, &recurring.project)?;
+        if recurring.start >= recurring.end {
+            return Err(CommandExecutionError::StartAfterEnd);
+        }
+    }
+
+    // a past day is over, today only until now or the start of the running chunk
+    let mut until = if date == now.date() {
+        now.time()
+    } else {
+        NaiveTime::MIN - Duration::minutes(1)
+    };
+    if let Some(running) = &day.running {
+        until = until.min(running.start_time);
+    }
+
+    let plan = plan(&recurring, &day, until);
+    let chunks: Vec<Chunk> = plan
+        .iter()
+        .filter(|(_, result)| result.is_ok())
+        .map(|(recurring, _)| Chunk {
+            project: recurring.project.clone(),
+            description: recurring.description.clone(),
+            tags: recurring.tags.clone(),
+            start_time: Some(recurring.start),
+            end_time: recurring.end,
+            ..Chunk::default()
+        })
+        .collect();
+
+    let warnings = if chunks.is_empty() {
+        vec![]
+    } else {
+        track_chunks(&mut day, chunks, &config)?
+    };
+
+    Ok(AppliedSchedule {
+        chunks: plan
+            .into_iter()
+            .map(|(recurring, result)| (recurring.clone(), result))
+            .collect(),
+        warnings,
+    })
+}
+
+/// Decides for each recurring chunk whether it fits into an untracked gap before `until`,
+/// taking the chunks accepted before it into account.
+fn plan<'a>(
+    recurring: &[&'a RecurringChunk],
+    day: &Day,
+    until: NaiveTime,
+) -> Vec<(&'a RecurringChunk, Result<(), Skipped>)> {
+    let gaps = untracked_gaps(day, until);
+    let mut sorted = recurring.to_vec();
+    sorted.sort_by_key(|recurring| recurring.start);
+
+    let mut accepted: Vec<&RecurringChunk> = vec![];
+    sorted
+        .into_iter()
+        .map(|recurring| {
+            let result = if recurring.end > until {
+                Err(Skipped::NotOver)
+            } else if !gaps
+                .iter()
+                .any(|(start, end)| recurring.start >= *start && recurring.end <= *end)
+                || accepted.iter().any(|other| recurring.start < other.end)
+            {
+                Err(Skipped::Conflict)
+            } else {
+                accepted.push(recurring);
+                Ok(())
+            };
+            (recurring, result)
+        })
+        .collect()
+}
+
+mod weekdays_format {
+    use chrono::Weekday;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[allow(clippy::ptr_arg)]
+    pub fn serialize<S>(weekdays: &Vec<Weekday>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        weekdays
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Weekday>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|weekday| {
+                weekday
+                    .parse()
+                    .map_err(|_| serde::de::Error::custom(format!("'{weekday}' is not a weekday")))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_util::{chunk, date, day, time};
+
+    fn recurring(start: NaiveTime, end: NaiveTime, project: &str) -> RecurringChunk {
+        RecurringChunk {
+            weekdays: vec![],
+            start,
+            end,
+            project: project.to_string(),
+            description: None,
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_parse_recurring_chunk() {
+        let recurring: RecurringChunk = serde_json::from_str(
+            r#"{ "weekdays": ["Mon", "friday"], "start": "09:00", "end": "09:15",
+                 "project": "kyoshi", "description": "Stand-up" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(recurring.weekdays, vec![Weekday::Mon, Weekday::Fri]);
+        assert_eq!(recurring.description.as_deref(), Some("Stand-up"));
+        // the test date is a Monday
+        assert!(recurring.happens_on(date()));
+        assert!(!recurring.happens_on(date().succ_opt().unwrap()));
+        assert!(serde_json::to_string(&recurring)
+            .unwrap()
+            .contains(r#""weekdays":["Mon","Fri"]"#));
+
+        assert!(serde_json::from_str::<RecurringChunk>(
+            r#"{ "weekdays": ["Someday"], "start": "09:00", "end": "09:15", "project": "kyoshi" }"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_plan() {
+        let day = Day {
+            check_in_time: time(8, 30),
+            ..day(vec![Chunk {
+                start_time: Some(time(10, 0)),
+                ..chunk("korra", time(11, 0))
+            }])
+        };
+
+        let before_check_in = recurring(time(8, 0), time(8, 15), "kyoshi");
+        let stand_up = recurring(time(9, 0), time(9, 15), "kyoshi");
+        let overlapping_stand_up = recurring(time(9, 10), time(9, 20), "kyoshi");
+        let overlapping_chunk = recurring(time(9, 45), time(10, 15), "kyoshi");
+        let after_chunk = recurring(time(11, 0), time(11, 30), "kyoshi");
+        let not_over = recurring(time(11, 30), time(12, 30), "kyoshi");
+
+        let plan = plan(
+            &[
+                &not_over,
+                &stand_up,
+                &before_check_in,
+                &overlapping_stand_up,
+                &overlapping_chunk,
+                &after_chunk,
+            ],
+            &day,
+            time(12, 0),
+        );
+
+        assert_eq!(
+            plan.iter()
+                .map(|(recurring, result)| (recurring.start, result))
+                .collect::<Vec<_>>(),
+            vec![
+                (time(8, 0), &Err(Skipped::Conflict)),
+                (time(9, 0), &Ok(())),
+                (time(9, 10), &Err(Skipped::Conflict)),
+                (time(9, 45), &Err(Skipped::Conflict)),
+                (time(11, 0), &Ok(())),
+                (time(11, 30), &Err(Skipped::NotOver)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use chron::{
+    check_in, compute_status, get_days_of_month, get_days_of_week, load_available_days,
+    load_config, load_day, status_json, summary_json, take_break, track, Error, Period, Violation,
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Deserializer};
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrackRequest {
+    project: String,
+
+    #[serde(default)]
+    description: Option<String>,
+
+    #[serde(default)]
+    tags: Vec<String>,
+
+    /// Only set if the chunk does not start where the previous one ended.
+    #[serde(default, deserialize_with = "optional_time")]
+    start_time: Option<NaiveTime>,
+
+    /// The current time if not set.
+    #[serde(default, deserialize_with = "optional_time")]
+    end_time: Option<NaiveTime>,
+}
+
+/// Deserializes a time like `09:30`, as stored in the files of the days.
+fn optional_time<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveTime>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|time| NaiveTime::parse_from_str(&time, "%H:%M").map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[derive(Deserialize, Default)]
+struct BreakRequest {
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Errors of a request, answered with the status code and the message.
+enum ApiError {
+    Unauthorized,
+    InvalidHost,
+    NotFound,
+    UnsupportedMediaType,
+    BadRequest(String),
+    Command(Error),
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        ApiError::Command(error)
+    }
+}
+
+impl ApiError {
+    fn status_code(&self) -> u16 {
+        match self {
+            ApiError::Unauthorized => 401,
+            ApiError::InvalidHost => 403,
+            ApiError::NotFound => 404,
+            ApiError::UnsupportedMediaType => 415,
+            // the data or config files could not be read or written
+            ApiError::Command(
+                Error::IoError(_) | Error::InvalidJsonFormat(_) | Error::UnexpectedError(_),
+            ) => 500,
+            ApiError::BadRequest(_) | ApiError::Command(_) => 400,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            ApiError::Unauthorized => "Missing or invalid token".to_string(),
+            ApiError::InvalidHost => {
+                "Only requests to 127.0.0.1 or localhost are accepted".to_string()
+            }
+            ApiError::NotFound => "Not found".to_string(),
+            ApiError::UnsupportedMediaType => {
+                "POST requests need the header 'Content-Type: application/json'".to_string()
+            }
+            ApiError::BadRequest(message) => message.clone(),
+            ApiError::Command(e) => e.to_string(),
+        }
+    }
+}
+
+/// Serves the HTTP API on localhost until the process is stopped.
+///
+/// Requests need the token as bearer token, a random one is generated if none is given,
+/// unless no token is required at all.
+///
+/// # Errors
+///
+/// Fails if the port cannot be bound.
+///
+/// # Panics
+///
+/// Panics if `tiny_http` rejects the constant content type header.
+pub fn serve(port: u16, token: Option<&str>, no_token: bool) -> Result<(), Error> {
+    let server =
+        Server::http(("127.0.0.1", port)).map_err(|e| Error::UnexpectedError(e.to_string()))?;
+
+    let token = match (token, no_token) {
+        (_, true) => None,
+        (Some(token), false) => Some(token.to_string()),
+        (None, false) => Some(generate_token()?),
+    };
+
+    println!("Serving the chron API on http://127.0.0.1:{port}");
+    match &token {
+        Some(token) => println!("Authorization: Bearer {token}"),
+        None => println!("WARNING: Any local process can track time without a token"),
+    }
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match handle(&mut request, port, token.as_deref()) {
+            Ok(body) => (200, body),
+            Err(e) => (e.status_code(), json!({ "error": e.message() })),
+        };
+
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes("Content-Type", "application/json")
+                    .expect("header should be valid"),
+            );
+        if let Err(e) = request.respond(response) {
+            println!("Could not respond to a request: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns 128 random bits from the operating system as hex.
+fn generate_token() -> Result<String, Error> {
+    let mut bytes = [0; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| Error::UnexpectedError(e.to_string()))?;
+
+    Ok(format!("{:032x}", u128::from_be_bytes(bytes)))
+}
+
+fn header<'a>(request: &'a Request, field: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(field))
+        .map(|header| header.value.as_str())
+}
+
+fn handle(
+    request: &mut Request,
+    port: u16,
+    token: Option<&str>,
+) -> Result<serde_json::Value, ApiError> {
+    // protects against DNS rebinding, where a website resolves its own host to 127.0.0.1
+    if !header(request, "Host").is_some_and(|host| is_local_host(host, port)) {
+        return Err(ApiError::InvalidHost);
+    }
+
+    if token
+        .is_some_and(|token| header(request, "Authorization") != Some(&format!("Bearer {token}")))
+    {
+        return Err(ApiError::Unauthorized);
+    }
+
+    // browsers cannot send JSON to other origins without a preflight request
+    if *request.method() == Method::Post
+        && !header(request, "Content-Type").is_some_and(is_json_content_type)
+    {
+        return Err(ApiError::UnsupportedMediaType);
+    }
+
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    let now = chrono::offset::Local::now().naive_local();
+
+    match (request.method(), path) {
+        (Method::Get, "/status") => status(now, &[]),
+        (Method::Get, "/report/day") => report(&Period::Day(query_date(query)?)),
+        (Method::Get, "/report/week") => report(&Period::Week(query_date(query)?)),
+        (Method::Get, "/report/month") => report(&Period::Month(query_date(query)?)),
+        (Method::Post, "/check-in") => {
+            check_in(now)?;
+            status(now, &[])
+        }
+        (Method::Post, "/track") => {
+            let body: TrackRequest = read_body(request)?;
+            let warnings = track(
+                now,
+                body.start_time,
+                body.end_time.unwrap_or(now.time()),
+                body.project,
+                body.description,
+                body.tags,
+            )?;
+            status(now, &warnings)
+        }
+        (Method::Post, "/break") => {
+            let body: BreakRequest = read_body(request)?;
+            let warnings = take_break(now, body.description)?;
+            status(now, &warnings)
+        }
+        _ => Err(ApiError::NotFound),
+    }
+}
+
+/// Returns the status of today, with the warnings of a request that tracked time.
+fn status(now: NaiveDateTime, warnings: &[Violation]) -> Result<serde_json::Value, ApiError> {
+    let day = load_day(now.date())?;
+    let mut status = status_json(&compute_status(day, now.time(), &load_config()?));
+    status["warnings"] = warnings.iter().map(ToString::to_string).collect();
+
+    Ok(status)
+}
+
+/// Whether the `Host` header names the server itself, i.e. 127.0.0.1 or localhost.
+fn is_local_host(host: &str, port: u16) -> bool {
+    let (name, host_port) = host.rsplit_once(':').unwrap_or((host, ""));
+    (name == "127.0.0.1" || name.eq_ignore_ascii_case("localhost"))
+        && (host_port.is_empty() || host_port == port.to_string())
+}
+
+fn is_json_content_type(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case("application/json"))
+}
+
+fn report(period: &Period) -> Result<serde_json::Value, ApiError> {
+    let dates = match period {
+        Period::Day(date) => vec![*date],
+        Period::Week(date) => get_days_of_week(*date),
+        Period::Month(date) => get_days_of_month(*date),
+    };
+    let days = load_available_days(&dates);
+    let config = load_config()?;
+
+    Ok(summary_json(&dates, &days, &config))
+}
+
+/// Returns the date given as `date=YYYY-MM-DD` in the query, or today.
+fn query_date(query: &str) -> Result<NaiveDate, ApiError> {
+    match query
+        .split('&')
+        .find_map(|parameter| parameter.strip_prefix("date="))
+    {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+            ApiError::BadRequest(format!(
+                "The date '{date}' does not match expected format 'YYYY-MM-DD'"
+            ))
+        }),
+        None => Ok(chrono::offset::Local::now().date_naive()),
+    }
+}
+
+/// Reads the JSON body of a request, an empty body counts as an empty object.
+fn read_body<T: for<'de> Deserialize<'de>>(request: &mut Request) -> Result<T, ApiError> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    if body.trim().is_empty() {
+        body = "{}".to_string();
+    }
+
+    serde_json::from_str(&body).map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_date() {
+        assert_eq!(
+            query_date("date=2023-11-20").ok(),
+            NaiveDate::from_ymd_opt(2023, 11, 20)
+        );
+        assert_eq!(
+            query_date("x=1&date=2023-11-20").ok(),
+            NaiveDate::from_ymd_opt(2023, 11, 20)
+        );
+        assert_eq!(
+            query_date("").ok(),
+            Some(chrono::offset::Local::now().date_naive())
+        );
+        assert!(query_date("date=20.11.2023").is_err());
+    }
+
+    #[test]
+    fn test_track_request() {
+        let request: TrackRequest = serde_json::from_str(
+            r#"{ "project": "kyoshi", "description": "review", "startTime": "09:30", "endTime": "10:00" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.project, "kyoshi");
+        assert_eq!(request.description, Some("review".to_string()));
+        assert!(request.tags.is_empty());
+        assert_eq!(request.start_time, NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(request.end_time, NaiveTime::from_hms_opt(10, 0, 0));
+    }
+
+    #[test]
+    fn test_is_local_host() {
+        assert!(is_local_host("127.0.0.1:7373", 7373));
+        assert!(is_local_host("localhost:7373", 7373));
+        assert!(is_local_host("localhost", 7373));
+        assert!(!is_local_host("localhost:8080", 7373));
+        assert!(!is_local_host("attacker.example:7373", 7373));
+        assert!(!is_local_host("127.0.0.1.attacker.example:7373", 7373));
+    }
+
+    #[test]
+    fn test_is_json_content_type() {
+        assert!(is_json_content_type("application/json"));
+        assert!(is_json_content_type("application/json; charset=utf-8"));
+        assert!(!is_json_content_type("text/plain"));
+        assert!(!is_json_content_type("application/x-www-form-urlencoded"));
+    }
+
+    #[test]
+    fn test_error_status_code() {
+        assert_eq!(ApiError::Command(Error::StartAfterEnd).status_code(), 400);
+        assert_eq!(
+            ApiError::Command(Error::InvalidJsonFormat(String::new())).status_code(),
+            500
+        );
+    }
+
+    #[test]
+    fn test_generate_token() {
+        assert_eq!(generate_token().unwrap().len(), 32);
+        assert_ne!(generate_token().unwrap(), generate_token().unwrap());
+    }
+}
@@ -0,0 +1,251 @@
+use crate::commands::{Chunk, Day};
+use crate::config::Config;
+use crate::reporting::{counted_work_duration, format_duration};
+use chrono::{Duration, NaiveTime};
+use serde_json::json;
+
+/// Snapshot of the current day.
+pub struct Status {
+    pub check_in_time: NaiveTime,
+    /// Project and end time of the last finished chunk.
+    pub last_chunk: Option<(String, NaiveTime)>,
+    /// Project of the running chunk, if one was started with `chron start`.
+    pub current_project: Option<String>,
+    /// Start of the chunk that is currently being worked.
+    pub current_since: NaiveTime,
+    /// Work of the day so far, including the current chunk.
+    pub total: Duration,
+    /// Time left until the target of the day, negative for overtime.
+    pub remaining: Option<Duration>,
+    pub now: NaiveTime,
+}
+
+/// Computes the status of the day at the given time, counting the current chunk as work.
+///
+/// A configured `breakDeduction` is applied to the total.
+#[must_use]
+pub fn compute_status(mut day: Day, now: NaiveTime, config: &Config) -> Status {
+    day.chunks.sort_by_key(|chunk| chunk.end_time);
+
+    let last_chunk = day
+        .chunks
+        .last()
+        .map(|chunk| (chunk.project.clone(), chunk.end_time));
+
+    let (current_project, current_since) = match &day.running {
+        Some(running) => (Some(running.project.clone()), running.start_time),
+        None => (
+            None,
+            last_chunk
+                .as_ref()
+                .map_or(day.check_in_time, |(_, end_time)| *end_time),
+        ),
+    };
+
+    // the current chunk counts as work, unless it is a running break,
+    // and a missing break is deducted like in the reports
+    let mut worked = day.clone();
+    if current_project.as_deref() != Some("break") && now > current_since {
+        worked.chunks.push(Chunk {
+            project: current_project.clone().unwrap_or_default(),
+            start_time: Some(current_since),
+            end_time: now,
+            ..Chunk::default()
+        });
+    }
+    let total = counted_work_duration(&worked, config);
+    let target = config.target_for(day.date);
+
+    Status {
+        check_in_time: day.check_in_time,
+        last_chunk,
+        current_project,
+        current_since,
+        total,
+        remaining: target.map(|target| target - total),
+        now,
+    }
+}
+
+/// Formats the status as a list, as printed by `chron status`.
+#[must_use]
+pub fn format_status(status: &Status) -> String {
+    let mut lines = vec![format!(
+        "- checked in: {}",
+        status.check_in_time.format("%H:%M")
+    )];
+
+    lines.push(match &status.last_chunk {
+        Some((project, end_time)) => {
+            format!("- last chunk: {project} until {}", end_time.format("%H:%M"))
+        }
+        None => "- last chunk: none".to_string(),
+    });
+
+    lines.push(format!(
+        "- current chunk: {}since {}, {}",
+        status
+            .current_project
+            .as_ref()
+            .map_or(String::new(), |project| format!("{project} ")),
+        status.current_since.format("%H:%M"),
+        format_duration(&(status.now - status.current_since))
+    ));
+
+    lines.push(format!("- total today: {}", format_duration(&status.total)));
+
+    if let Some(remaining) = status.remaining {
+        if remaining < Duration::zero() {
+            lines.push(format!("- overtime: {}", format_duration(&-remaining)));
+        } else {
+            lines.push(format!("- remaining: {}", format_duration(&remaining)));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Returns the status for the HTTP API, in minutes.
+#[must_use]
+pub fn status_json(status: &Status) -> serde_json::Value {
+    json!({
+        "checkInTime": status.check_in_time.format("%H:%M").to_string(),
+        "lastChunk": status.last_chunk.as_ref().map(|(project, end_time)| json!({
+            "project": project,
+            "endTime": end_time.format("%H:%M").to_string(),
+        })),
+        "currentProject": status.current_project,
+        "currentSince": status.current_since.format("%H:%M").to_string(),
+        "totalMinutes": status.total.num_minutes(),
+        "remainingMinutes": status.remaining.map(|remaining| remaining.num_minutes()),
+    })
+}
+
+/// Formats the status as a single line, e.g. for a shell prompt.
+#[must_use]
+pub fn format_short_status(status: &Status) -> String {
+    let elapsed = format_hours_minutes(&(status.now - status.current_since));
+
+    let mut parts = vec![
+        match &status.current_project {
+            Some(project) => format!("{project} {elapsed}"),
+            None => format!("{elapsed} since {}", status.current_since.format("%H:%M")),
+        },
+        format!("today {}", format_hours_minutes(&status.total)),
+    ];
+
+    if let Some(remaining) = status.remaining {
+        if remaining < Duration::zero() {
+            parts.push(format!("{} over", format_hours_minutes(&-remaining)));
+        } else {
+            parts.push(format!("{} left", format_hours_minutes(&remaining)));
+        }
+    }
+
+    parts.join(" | ")
+}
+
+/// Formats a duration like `1h 30m`.
+#[must_use]
+pub fn format_hours_minutes(duration: &Duration) -> String {
+    let hours = duration.num_hours();
+    let minutes = duration.num_minutes() - hours * 60;
+    format!("{hours}h {minutes}m")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::commands::RunningChunk;
+    use crate::rules::{BreakDeduction, Rule};
+    use crate::test_util::{chunk, time};
+
+    #[cfg(test)]
+    use pretty_assertions::assert_eq;
+
+    fn day() -> Day {
+        crate::test_util::day(vec![
+            chunk("kyoshi", time(10, 0)),
+            chunk("break", time(10, 30)),
+        ])
+    }
+
+    fn config(target_hours: f64) -> Config {
+        Config {
+            daily_target_hours: Some(target_hours),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let status = compute_status(day(), time(11, 15), &config(8.0));
+
+        let expected = "\
+- checked in: 08:00
+- last chunk: break until 10:30
+- current chunk: since 10:30, 0.75h (0h 45m)
+- total today: 2.75h (2h 45m)
+- remaining: 5.25h (5h 15m)";
+
+        assert_eq!(expected, format_status(&status));
+        assert_eq!(
+            "0h 45m since 10:30 | today 2h 45m | 5h 15m left",
+            format_short_status(&status)
+        );
+    }
+
+    #[test]
+    fn test_status_running() {
+        let mut day = day();
+        day.running = Some(RunningChunk {
+            project: "korra".to_string(),
+            start_time: time(11, 0),
+            ..RunningChunk::default()
+        });
+
+        let status = compute_status(day, time(11, 15), &Config::default());
+
+        let expected = "\
+- checked in: 08:00
+- last chunk: break until 10:30
+- current chunk: korra since 11:00, 0.25h (0h 15m)
+- total today: 2.25h (2h 15m)";
+
+        assert_eq!(expected, format_status(&status));
+        assert_eq!("korra 0h 15m | today 2h 15m", format_short_status(&status));
+    }
+
+    #[test]
+    fn test_status_overtime() {
+        let status = compute_status(day(), time(11, 15), &config(2.0));
+
+        assert_eq!(
+            "0h 45m since 10:30 | today 2h 45m | 0h 45m over",
+            format_short_status(&status)
+        );
+    }
+
+    #[test]
+    fn test_status_break_deduction() {
+        let config = Config {
+            rules: vec![Rule::MinBreak {
+                after_hours: 6.0,
+                break_minutes: 30,
+                min_block_minutes: 0,
+            }],
+            break_deduction: Some(BreakDeduction::LargestChunk),
+            ..config(8.0)
+        };
+
+        // 7h without a break, including the current chunk
+        let day = crate::test_util::day(vec![chunk("kyoshi", time(12, 0))]);
+        let status = compute_status(day, time(15, 0), &config);
+
+        assert_eq!(
+            "3h 0m since 12:00 | today 6h 30m | 1h 30m left",
+            format_short_status(&status)
+        );
+    }
+}
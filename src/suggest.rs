@@ -0,0 +1,101 @@
+use crate::commands::Day;
+use crate::reporting::{day_spans, UNTRACKED};
+use crate::time_input::last_chunk_end;
+use chrono::{Duration, NaiveTime};
+
+/// A chunk proposed from local data like commits, which is only tracked once accepted.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Proposal {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub project: String,
+    pub description: Option<String>,
+}
+
+impl std::fmt::Display for Proposal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{} {}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M"),
+            self.project
+        )?;
+        if let Some(description) = &self.description {
+            write!(f, " {description}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Chunks proposed for the untracked time of today.
+#[derive(Debug, Default, PartialEq)]
+pub struct Suggestions {
+    /// Sorted and not overlapping each other.
+    pub proposals: Vec<Proposal>,
+    /// Data that was left out, and why.
+    pub warnings: Vec<String>,
+}
+
+/// Returns the untracked gaps of the day, including the one from the last chunk until now.
+#[must_use]
+pub fn untracked_gaps(day: &Day, now: NaiveTime) -> Vec<(NaiveTime, NaiveTime)> {
+    let mut day = day.clone();
+    day.chunks.sort_by_key(|chunk| chunk.end_time);
+
+    let mut gaps: Vec<_> = day_spans(&day)
+        .iter()
+        .filter(|span| span.project == UNTRACKED)
+        .map(|span| (span.start, span.end))
+        .collect();
+
+    let last_end = last_chunk_end(&day);
+    if now - last_end >= Duration::minutes(1) {
+        gaps.push((last_end, now));
+    }
+
+    gaps
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::commands::Chunk;
+    use crate::test_util::{chunk, day, time};
+
+    #[test]
+    fn test_untracked_gaps() {
+        // retro-tracked chunks are appended, so they are not sorted
+        let day = day(vec![
+            Chunk {
+                start_time: Some(time(10, 0)),
+                ..chunk("korra", time(11, 0))
+            },
+            chunk("kyoshi", time(9, 0)),
+        ]);
+
+        assert_eq!(
+            untracked_gaps(&day, time(12, 0)),
+            vec![(time(9, 0), time(10, 0)), (time(11, 0), time(12, 0))]
+        );
+        assert_eq!(
+            untracked_gaps(&day, time(11, 0)),
+            vec![(time(9, 0), time(10, 0))]
+        );
+    }
+
+    #[test]
+    fn test_format_proposal() {
+        let mut proposal = Proposal {
+            start: time(9, 0),
+            end: time(9, 40),
+            project: "kyoshi".to_string(),
+            description: Some("Fix the login".to_string()),
+        };
+        assert_eq!(proposal.to_string(), "09:00-09:40 kyoshi Fix the login");
+
+        proposal.description = None;
+        assert_eq!(proposal.to_string(), "09:00-09:40 kyoshi");
+    }
+}
@@ -0,0 +1,75 @@
+/// Returns the tags written as `#tag` in the description followed by the given tags,
+/// without duplicates.
+///
+/// A tag must start with a letter, so that ticket numbers like `#123` are no tags.
+pub fn collect_tags(description: Option<&str>, tags: Vec<String>) -> Vec<String> {
+    let inline_tags = description
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(|word| {
+            word.chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                .collect::<String>()
+        });
+
+    let given_tags = tags
+        .into_iter()
+        .map(|tag| tag.trim_start_matches('#').to_string());
+
+    let mut collected: Vec<String> = vec![];
+    for tag in inline_tags.chain(given_tags) {
+        if tag.starts_with(char::is_alphabetic) && !collected.contains(&tag) {
+            collected.push(tag);
+        }
+    }
+
+    collected
+}
+
+/// Returns the tags that were given explicitly, i.e. are not inline in the description.
+#[must_use]
+pub fn explicit_tags(description: Option<&str>, tags: Vec<String>) -> Vec<String> {
+    let inline_tags = collect_tags(description, vec![]);
+    tags.into_iter()
+        .filter(|tag| !inline_tags.contains(tag))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_tags() {
+        assert_eq!(collect_tags(None, vec![]), Vec::<String>::new());
+
+        assert_eq!(
+            collect_tags(
+                Some("#review of feature #123, then #meeting."),
+                vec!["meeting".to_string(), "#planning".to_string()]
+            ),
+            vec![
+                "review".to_string(),
+                "meeting".to_string(),
+                "planning".to_string()
+            ]
+        );
+
+        assert_eq!(
+            collect_tags(Some("issue#12 # #-x"), vec![]),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_explicit_tags() {
+        assert_eq!(
+            explicit_tags(
+                Some("#review of feature #123"),
+                vec!["review".to_string(), "planning".to_string()]
+            ),
+            vec!["planning".to_string()]
+        );
+    }
+}
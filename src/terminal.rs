@@ -0,0 +1,12 @@
+//! Commands that ask questions on the terminal or print as they go, e.g. a countdown or the
+//! chunks they track. They use the current time instead of taking it as an argument.
+
+mod pomodoro;
+mod remind;
+mod review;
+mod suggest;
+
+pub use pomodoro::{pomodoro, PomodoroOptions};
+pub use remind::remind;
+pub use review::review;
+pub use suggest::track_proposals;
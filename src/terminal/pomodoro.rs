@@ -0,0 +1,177 @@
+use crate::cli::print_warnings;
+use chron::{check_project_configured, last_chunk_end, load_config, load_tracking_day, Error};
+use chrono::{Duration, NaiveTime};
+use std::io::Write;
+
+/// Lengths given on the command line instead of the configured ones.
+#[derive(PartialEq, Debug, Default)]
+pub struct PomodoroOptions {
+    pub work: Option<Duration>,
+    pub pause: Option<Duration>,
+    /// Number of work intervals, until the process is stopped if not given.
+    pub rounds: Option<u32>,
+}
+
+/// Counts down alternating work and break intervals, tracking a chunk at the end of each.
+///
+/// # Errors
+///
+/// Fails if an interval is shorter than a minute, the project is not configured, a chunk
+/// is running, or like [`chron::track`].
+pub fn pomodoro(
+    project: &str,
+    description: Option<&str>,
+    tags: &[String],
+    options: &PomodoroOptions,
+) -> Result<(), Error> {
+    let config = load_config()?;
+    check_project_configured(&config, project)?;
+
+    let lengths = config.pomodoro.unwrap_or_default();
+    let work = options
+        .work
+        .unwrap_or(Duration::minutes(lengths.work_minutes));
+    let pause = options
+        .pause
+        .unwrap_or(Duration::minutes(lengths.break_minutes));
+    if work < Duration::minutes(1) || pause < Duration::minutes(1) {
+        return Err(Error::IntervalTooShort);
+    }
+
+    let now = chrono::offset::Local::now();
+    let day = load_tracking_day(now.date_naive())?;
+    if let Some(running) = &day.running {
+        return Err(Error::ChunkRunning(running.project.clone()));
+    }
+
+    let mut start = first_start(now.time(), last_chunk_end(&day));
+    for interval in intervals(options.rounds) {
+        match interval {
+            Interval::Work(round) => {
+                countdown(&format!("{round}. {project}"), work)?;
+                let end = chrono::offset::Local::now().naive_local();
+                print_warnings(&chron::track(
+                    end,
+                    start.take(),
+                    end.time(),
+                    project.to_string(),
+                    description.map(str::to_string),
+                    tags.to_vec(),
+                )?);
+            }
+            Interval::Break(round) => {
+                countdown(&format!("{round}. break"), pause)?;
+                let end = chrono::offset::Local::now().naive_local();
+                print_warnings(&chron::track(
+                    end,
+                    None,
+                    end.time(),
+                    "break".to_string(),
+                    None,
+                    vec![],
+                )?);
+            }
+        }
+        print!("\x07");
+    }
+
+    if let Some(rounds) = options.rounds {
+        println!("Done after {rounds} rounds.");
+    }
+
+    Ok(())
+}
+
+/// An interval of a pomodoro with its round, tracked as a chunk when it is over.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Interval {
+    Work(u32),
+    Break(u32),
+}
+
+/// Returns the intervals of a pomodoro, alternating work and breaks and ending with the
+/// work of the last round, or going on forever without a number of rounds.
+fn intervals(rounds: Option<u32>) -> impl Iterator<Item = Interval> {
+    (1..)
+        .flat_map(|round| [Interval::Work(round), Interval::Break(round)])
+        .take_while(move |interval| {
+            rounds.is_none_or(|rounds| match interval {
+                Interval::Work(round) => *round <= rounds,
+                Interval::Break(round) => *round < rounds,
+            })
+        })
+}
+
+/// Returns the start of the first chunk, if the pomodoro does not start where the last
+/// chunk ended. Less than a minute in between is added to the first chunk.
+fn first_start(now: NaiveTime, last_end: NaiveTime) -> Option<NaiveTime> {
+    Some(now).filter(|now| *now - last_end >= Duration::minutes(1))
+}
+
+/// Shows the remaining time of the interval until it is over.
+fn countdown(label: &str, length: Duration) -> Result<(), Error> {
+    let end = chrono::offset::Local::now() + length;
+
+    loop {
+        let remaining = end - chrono::offset::Local::now();
+        if remaining <= Duration::zero() {
+            println!("\r{label} done    ");
+            return Ok(());
+        }
+
+        print!("\r{label} {} ", format_remaining(remaining));
+        std::io::stdout().flush()?;
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+}
+
+/// Formats a remaining time like `24:59`, rounding up to full seconds.
+fn format_remaining(remaining: Duration) -> String {
+    let seconds = (remaining + Duration::milliseconds(999)).num_seconds();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn time(hours: u32, minutes: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hours, minutes, 0).unwrap()
+    }
+
+    #[test]
+    fn test_intervals() {
+        assert_eq!(
+            intervals(Some(2)).collect::<Vec<_>>(),
+            vec![Interval::Work(1), Interval::Break(1), Interval::Work(2)]
+        );
+        assert_eq!(
+            intervals(Some(1)).collect::<Vec<_>>(),
+            vec![Interval::Work(1)]
+        );
+        assert_eq!(intervals(None).take(5).last(), Some(Interval::Work(3)));
+    }
+
+    #[test]
+    fn test_first_start() {
+        assert_eq!(first_start(time(10, 0), time(9, 0)), Some(time(10, 0)));
+        assert_eq!(
+            first_start(time(10, 0) + Duration::seconds(59), time(10, 0)),
+            None
+        );
+        assert_eq!(first_start(time(10, 0), time(10, 0)), None);
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::minutes(25)), "25:00");
+        assert_eq!(
+            format_remaining(Duration::minutes(25) - Duration::milliseconds(1)),
+            "25:00"
+        );
+        assert_eq!(format_remaining(Duration::seconds(61)), "01:01");
+        assert_eq!(format_remaining(Duration::milliseconds(1)), "00:01");
+        assert_eq!(format_remaining(Duration::minutes(90)), "90:00");
+    }
+}
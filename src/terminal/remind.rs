@@ -0,0 +1,66 @@
+use chron::{due_reminder, load_config, load_day, Error, SentReminders};
+
+/// How often today's file is checked.
+// `Duration::from_mins` needs Rust 1.91
+#[allow(clippy::duration_suboptimal_units)]
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Watches today's chunks and sends reminders until the process is stopped.
+///
+/// # Errors
+///
+/// Fails if the config cannot be loaded.
+pub fn remind() -> Result<(), Error> {
+    let config = load_config()?;
+    let reminders = config.reminders.clone().unwrap_or_default();
+    let mut sent = SentReminders::default();
+
+    println!(
+        "Reminding after {} minutes without tracking",
+        reminders.interval_minutes
+    );
+
+    loop {
+        let now = chrono::offset::Local::now().naive_local();
+
+        match load_day(now.date()) {
+            Ok(day) => {
+                if let Some(reminder) =
+                    due_reminder(Some(&day), now, &config, &reminders, &mut sent)
+                {
+                    notify(&reminder.to_string());
+                }
+            }
+            Err(Error::NotCheckedIn(_)) => {
+                if let Some(reminder) = due_reminder(None, now, &config, &reminders, &mut sent) {
+                    notify(&reminder.to_string());
+                }
+            }
+            Err(e) => println!("{e}"),
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Sends a desktop notification, or rings the terminal bell if that is not possible.
+fn notify(message: &str) {
+    let result = if cfg!(target_os = "macos") {
+        std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "display notification \"{}\" with title \"chron\"",
+                message.replace('"', "'")
+            ))
+            .status()
+    } else {
+        std::process::Command::new("notify-send")
+            .args(["chron", message])
+            .status()
+    };
+
+    if !result.is_ok_and(|status| status.success()) {
+        print!("\x07");
+    }
+    println!("{} {message}", chrono::offset::Local::now().format("%H:%M"));
+}
@@ -0,0 +1,299 @@
+use crate::cli::print_warnings;
+use chron::{
+    check_project_configured, load_available_days, load_config, load_tracking_day, parse_duration,
+    parse_time_input, track_chunks, untracked_gaps, Chunk, Config, Day, Error, TimeInput,
+};
+use chrono::{Duration, NaiveTime};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+const ABORTED: &str = "Review aborted, nothing was written.";
+
+/// Number of past days whose descriptions are offered for completion.
+const RECENT_DAYS: i64 = 14;
+
+/// Completes the whole line with one of the candidates starting with it.
+#[derive(Default)]
+struct Candidates(Vec<String>);
+
+impl Completer for Candidates {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        _pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let matches = self
+            .0
+            .iter()
+            .filter(|candidate| candidate.starts_with(line))
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate.clone(),
+            })
+            .collect();
+        Ok((0, matches))
+    }
+}
+
+impl Hinter for Candidates {
+    type Hint = String;
+}
+
+impl Highlighter for Candidates {}
+
+impl Validator for Candidates {}
+
+impl Helper for Candidates {}
+
+/// Walks through the untracked gaps of today and asks what happened in them.
+///
+/// A gap can be split into several chunks, which are written once all gaps are reviewed
+/// and the user confirmed them.
+///
+/// # Errors
+///
+/// Fails if today was not checked in, a chunk is running, or if the day or the config
+/// cannot be loaded or saved.
+pub fn review() -> Result<(), Error> {
+    let now = chrono::offset::Local::now();
+    let mut day = load_tracking_day(now.date_naive())?;
+    let config = load_config()?;
+
+    if let Some(running) = &day.running {
+        return Err(Error::ChunkRunning(running.project.clone()));
+    }
+
+    let gaps = untracked_gaps(&day, now.time());
+    if gaps.is_empty() {
+        println!("Nothing to review, there are no untracked gaps today.");
+        return Ok(());
+    }
+
+    let mut editor: Editor<Candidates, DefaultHistory> = Editor::new()?;
+
+    let Some(chunks) = ask_chunks(&mut editor, &config, &gaps, now.time())? else {
+        println!("{ABORTED}");
+        return Ok(());
+    };
+
+    if chunks.is_empty() {
+        println!("Nothing was written.");
+        return Ok(());
+    }
+
+    println!();
+    for chunk in &chunks {
+        println!(
+            "  {}-{} {} {}",
+            chunk.start_time.unwrap_or(chunk.end_time).format("%H:%M"),
+            chunk.end_time.format("%H:%M"),
+            chunk.project,
+            chunk.description.as_deref().unwrap_or_default()
+        );
+    }
+    let prompt = format!("Write these {} chunks? [Y/n] ", chunks.len());
+    if ask(&mut editor, &prompt, &[])?.is_none_or(|answer| answer.eq_ignore_ascii_case("n")) {
+        println!("{ABORTED}");
+        return Ok(());
+    }
+
+    print_warnings(&track_chunks(&mut day, chunks, &config)?);
+
+    Ok(())
+}
+
+/// Asks for the chunks filling the gaps, or returns `None` if the user interrupted.
+fn ask_chunks(
+    editor: &mut Editor<Candidates, DefaultHistory>,
+    config: &Config,
+    gaps: &[(NaiveTime, NaiveTime)],
+    now: NaiveTime,
+) -> Result<Option<Vec<Chunk>>, Error> {
+    let today = chrono::offset::Local::now().date_naive();
+    let mut projects: Vec<String> = config
+        .projects
+        .iter()
+        .map(|project| project.name.clone())
+        .collect();
+    projects.push("break".to_string());
+
+    let dates: Vec<_> = (0..RECENT_DAYS)
+        .map(|offset| today - Duration::days(offset))
+        .collect();
+    let descriptions = recent_descriptions(&load_available_days(&dates));
+
+    let mut chunks = vec![];
+
+    for &(gap_start, gap_end) in gaps {
+        let mut start = gap_start;
+
+        while start < gap_end {
+            println!(
+                "\nUntracked {}-{} ({}m)",
+                start.format("%H:%M"),
+                gap_end.format("%H:%M"),
+                (gap_end - start).num_minutes()
+            );
+
+            let Some(project) = ask(editor, "project (empty to skip): ", &projects)? else {
+                return Ok(None);
+            };
+            if project.is_empty() {
+                break;
+            }
+            if let Err(e) = check_project_configured(config, &project) {
+                println!("{e}");
+                continue;
+            }
+
+            let prompt = format!(
+                "until (time or duration, empty for {}): ",
+                gap_end.format("%H:%M")
+            );
+            let Some(until) = ask(editor, &prompt, &[])? else {
+                return Ok(None);
+            };
+            let end = match parse_until(&until, start, gap_end, now) {
+                Ok(end) => end,
+                Err(message) => {
+                    println!("{message}");
+                    continue;
+                }
+            };
+
+            let Some(description) = ask(editor, "description: ", &descriptions)? else {
+                return Ok(None);
+            };
+            let description = Some(description).filter(|description| !description.is_empty());
+
+            chunks.push(Chunk {
+                project,
+                description,
+                start_time: Some(start),
+                end_time: end,
+                ..Chunk::default()
+            });
+            start = end;
+        }
+    }
+
+    Ok(Some(chunks))
+}
+
+/// Reads a line completing the candidates, or `None` if the user interrupted.
+fn ask(
+    editor: &mut Editor<Candidates, DefaultHistory>,
+    prompt: &str,
+    candidates: &[String],
+) -> Result<Option<String>, Error> {
+    editor.set_helper(Some(Candidates(candidates.to_vec())));
+
+    match editor.readline(prompt) {
+        Ok(line) => Ok(Some(line.trim().to_string())),
+        Err(ReadlineError::Interrupted | ReadlineError::Eof) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Parses the end of a chunk starting at `start` within a gap, given as a time, a duration
+/// like `20m`, or empty for the end of the gap.
+fn parse_until(
+    input: &str,
+    start: NaiveTime,
+    gap_end: NaiveTime,
+    now: NaiveTime,
+) -> Result<NaiveTime, String> {
+    let end = if input.is_empty() {
+        Some(gap_end)
+    } else if let Some(duration) = parse_duration(input) {
+        TimeInput::AfterLastChunk(duration).resolve(now, start).ok()
+    } else {
+        parse_time_input(input)?.resolve(now, start).ok()
+    };
+
+    let Some(end) = end.filter(|end| start < *end && *end <= gap_end) else {
+        return Err(format!(
+            "The chunk has to end between {} and {}.",
+            start.format("%H:%M"),
+            gap_end.format("%H:%M")
+        ));
+    };
+
+    Ok(end)
+}
+
+/// Returns the distinct descriptions of the days, the most recent first.
+///
+/// Expects the days to be sorted from the most recent one.
+fn recent_descriptions(days: &[Day]) -> Vec<String> {
+    let mut descriptions: Vec<String> = vec![];
+
+    for day in days {
+        for chunk in day.chunks.iter().rev() {
+            if let Some(description) = &chunk.description {
+                if !descriptions.contains(description) {
+                    descriptions.push(description.clone());
+                }
+            }
+        }
+    }
+
+    descriptions
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn time(hours: u32, minutes: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hours, minutes, 0).unwrap()
+    }
+
+    fn day(descriptions: &[&str]) -> Day {
+        Day {
+            chunks: descriptions
+                .iter()
+                .map(|description| Chunk {
+                    description: Some((*description).to_string()),
+                    ..Chunk::default()
+                })
+                .collect(),
+            ..Day::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_until() {
+        let start = time(9, 0);
+        let gap_end = time(10, 0);
+        let now = time(12, 0);
+
+        assert_eq!(parse_until("", start, gap_end, now), Ok(time(10, 0)));
+        assert_eq!(parse_until("20m", start, gap_end, now), Ok(time(9, 20)));
+        assert_eq!(parse_until("09:45", start, gap_end, now), Ok(time(9, 45)));
+        assert_eq!(parse_until("+15m", start, gap_end, now), Ok(time(9, 15)));
+        assert!(parse_until("2h", start, gap_end, now).is_err());
+        assert!(parse_until("08:30", start, gap_end, now).is_err());
+        assert!(parse_until("soon", start, gap_end, now).is_err());
+    }
+
+    #[test]
+    fn test_recent_descriptions() {
+        let today = day(&["review", "standup"]);
+        let yesterday = day(&["planning", "review"]);
+
+        assert_eq!(
+            recent_descriptions(&[today, yesterday]),
+            vec!["standup", "review", "planning"]
+        );
+    }
+}
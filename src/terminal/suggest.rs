@@ -0,0 +1,107 @@
+use crate::cli::print_warnings;
+use chron::{last_chunk_end, load_tracking_day, Error, Suggestions};
+use std::io::{BufRead, IsTerminal, Write};
+
+/// Prints the warnings of the suggestions and tracks the proposals that are accepted, each
+/// one after asking unless `yes` is set.
+///
+/// # Errors
+///
+/// Fails like [`chron::track`], or if the answer cannot be read.
+pub fn track_proposals(suggestions: Suggestions, yes: bool) -> Result<(), Error> {
+    for warning in &suggestions.warnings {
+        println!("{warning}");
+    }
+    let proposals = suggestions.proposals;
+    if proposals.is_empty() {
+        println!("Nothing to suggest.");
+        return Ok(());
+    }
+
+    let now = chrono::offset::Local::now().naive_local();
+    let mut last_end = last_chunk_end(&load_tracking_day(now.date())?);
+    let mut stdin = std::io::stdin().lock();
+    // piped answers have to be explicit, an empty line does not count as yes
+    let interactive = stdin.is_terminal();
+
+    for proposal in proposals {
+        if yes {
+            println!("{proposal}");
+        } else {
+            let answer = loop {
+                print!("{proposal}\n  Track this chunk? [Y/n/q] ");
+                std::io::stdout().flush()?;
+
+                let mut line = String::new();
+                let at_end = stdin.read_line(&mut line)? == 0;
+                if let Some(answer) = parse_answer(&line, at_end, interactive) {
+                    break answer;
+                }
+            };
+            match answer {
+                Answer::Yes => (),
+                Answer::No => continue,
+                Answer::Quit => break,
+            }
+        }
+
+        // the chunk only needs a start if the previous proposal was skipped
+        let start = Some(proposal.start).filter(|start| *start != last_end);
+        print_warnings(&chron::track(
+            now,
+            start,
+            proposal.end,
+            proposal.project,
+            proposal.description,
+            vec![],
+        )?);
+        last_end = proposal.end;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum Answer {
+    Yes,
+    No,
+    Quit,
+}
+
+/// Interprets a line read as answer, `None` if the question has to be asked again.
+///
+/// The end of the input quits, and an empty line only counts as yes in a terminal.
+fn parse_answer(line: &str, at_end: bool, interactive: bool) -> Option<Answer> {
+    if at_end {
+        return Some(Answer::Quit);
+    }
+
+    match line.trim().to_lowercase().as_str() {
+        "y" | "yes" => Some(Answer::Yes),
+        "" if interactive => Some(Answer::Yes),
+        "n" | "no" => Some(Answer::No),
+        "q" | "quit" => Some(Answer::Quit),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer() {
+        assert_eq!(parse_answer("\n", false, true), Some(Answer::Yes));
+        assert_eq!(parse_answer("y\n", false, false), Some(Answer::Yes));
+        assert_eq!(parse_answer("N\n", false, true), Some(Answer::No));
+        assert_eq!(parse_answer("q\n", false, true), Some(Answer::Quit));
+
+        // not in a terminal, the answer has to be explicit
+        assert_eq!(parse_answer("\n", false, false), None);
+        assert_eq!(parse_answer("maybe\n", false, true), None);
+
+        // the end of the input quits
+        assert_eq!(parse_answer("", true, true), Some(Answer::Quit));
+        assert_eq!(parse_answer("", true, false), Some(Answer::Quit));
+    }
+}
@@ -0,0 +1,38 @@
+//! Fixtures shared by the unit tests.
+#![allow(clippy::unwrap_used)]
+
+use crate::commands::{Chunk, Day};
+use chrono::{NaiveDate, NaiveTime};
+
+pub(crate) fn time(hours: u32, minutes: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hours, minutes, 0).unwrap()
+}
+
+/// The date of the test days, a Monday.
+pub(crate) fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2023, 11, 20).unwrap()
+}
+
+/// A chunk of the project that starts where the previous one ended.
+pub(crate) fn chunk(project: &str, end_time: NaiveTime) -> Chunk {
+    Chunk {
+        project: project.to_string(),
+        end_time,
+        ..Chunk::default()
+    }
+}
+
+/// A chunk of the project that starts where the previous one ended, given its end.
+pub(crate) fn chunk_until(project: &str, hours: u32, minutes: u32) -> Chunk {
+    chunk(project, time(hours, minutes))
+}
+
+/// A day checked in at 08:00.
+pub(crate) fn day(chunks: Vec<Chunk>) -> Day {
+    Day {
+        date: date(),
+        check_in_time: time(8, 0),
+        chunks,
+        ..Day::default()
+    }
+}
@@ -0,0 +1,55 @@
+use regex::Regex;
+
+/// Returns the ticket references in the description matching any of the patterns,
+/// without duplicates.
+///
+/// # Errors
+///
+/// Fails if a pattern is not a valid regular expression.
+pub fn extract_tickets(
+    description: Option<&str>,
+    patterns: &[String],
+) -> Result<Vec<String>, regex::Error> {
+    let Some(description) = description else {
+        return Ok(vec![]);
+    };
+
+    let mut tickets: Vec<String> = vec![];
+    for pattern in patterns {
+        for ticket in Regex::new(pattern)?.find_iter(description) {
+            let ticket = ticket.as_str().to_string();
+            if !tickets.contains(&ticket) {
+                tickets.push(ticket);
+            }
+        }
+    }
+
+    Ok(tickets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_tickets() {
+        let patterns = vec![r"#\d+".to_string(), r"ABC-\d+".to_string()];
+
+        assert_eq!(extract_tickets(None, &patterns), Ok(vec![]));
+
+        assert_eq!(
+            extract_tickets(
+                Some("develop feature #123 for ABC-42, see #123 and #review"),
+                &patterns
+            ),
+            Ok(vec!["#123".to_string(), "ABC-42".to_string()])
+        );
+
+        assert_eq!(
+            extract_tickets(Some("develop feature #123"), &[]),
+            Ok(vec![])
+        );
+
+        assert!(extract_tickets(Some("develop"), &["(".to_string()]).is_err());
+    }
+}
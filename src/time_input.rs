@@ -0,0 +1,263 @@
+use crate::commands::{CommandExecutionError, Day};
+use chrono::{Duration, NaiveTime};
+
+/// A point in time as typed by the user, which may depend on the current time or the
+/// tracked chunks.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum TimeInput {
+    /// The current time.
+    Now,
+    /// A clock time like `10:30`.
+    At(NaiveTime),
+    /// A duration before now like `-15m`.
+    Ago(Duration),
+    /// A duration after the end of the last chunk like `+45m`.
+    AfterLastChunk(Duration),
+}
+
+impl TimeInput {
+    /// Returns the clock time, given the current time and the end of the last chunk.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a relative time would be on the day before or after.
+    pub fn resolve(
+        &self,
+        now: NaiveTime,
+        last_chunk_end: NaiveTime,
+    ) -> Result<NaiveTime, CommandExecutionError> {
+        match self {
+            TimeInput::Now => Ok(now),
+            TimeInput::At(time) => Ok(*time),
+            TimeInput::Ago(duration) => match now.overflowing_sub_signed(*duration) {
+                (time, 0) => Ok(time),
+                _ => Err(CommandExecutionError::NoTrackingBeforeCheckIn),
+            },
+            TimeInput::AfterLastChunk(duration) => {
+                match last_chunk_end.overflowing_add_signed(*duration) {
+                    (time, 0) => Ok(time),
+                    _ => Err(CommandExecutionError::NoTrackingAfterCurrentTime),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the end of the last chunk of the day, or the check-in if there is none.
+#[must_use]
+pub fn last_chunk_end(day: &Day) -> NaiveTime {
+    day.chunks
+        .iter()
+        .map(|chunk| chunk.end_time)
+        .max()
+        .unwrap_or(day.check_in_time)
+}
+
+/// Parses a time like `10:30`, `1030`, `10.30`, `10:30am`, `noon`, `now`,
+/// `-15m`, `15 minutes ago` or `+45m`.
+///
+/// # Errors
+///
+/// Fails with a message for the user if the input is none of these.
+pub fn parse_time_input(input: &str) -> Result<TimeInput, String> {
+    let error = || {
+        format!(
+            "Your time input '{input}' does not match a time like '10:30', '1030', '10:30am', 'noon', '-15m', '15 minutes ago' or '+45m'"
+        )
+    };
+
+    let normalized = input.trim().to_lowercase();
+
+    if normalized == "now" {
+        return Ok(TimeInput::Now);
+    }
+    if let Some(duration) = normalized.strip_prefix('+') {
+        return parse_duration(duration)
+            .map(TimeInput::AfterLastChunk)
+            .ok_or_else(error);
+    }
+    if let Some(duration) = normalized
+        .strip_prefix('-')
+        .or_else(|| normalized.strip_suffix(" ago"))
+    {
+        return parse_duration(duration)
+            .map(TimeInput::Ago)
+            .ok_or_else(error);
+    }
+
+    parse_clock_time(&normalized)
+        .map(TimeInput::At)
+        .ok_or_else(error)
+}
+
+/// Parses a clock time like `10:30`, `1030`, `10.30`, `10:30am`, `10pm` or `noon`.
+fn parse_clock_time(input: &str) -> Option<NaiveTime> {
+    match input {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        _ => (),
+    }
+
+    let (time, offset) = if let Some(time) = input.strip_suffix("am") {
+        (time.trim_end(), Some(0))
+    } else if let Some(time) = input.strip_suffix("pm") {
+        (time.trim_end(), Some(12))
+    } else {
+        (input, None)
+    };
+
+    let (hours, minutes) = match time.split_once([':', '.']) {
+        Some((hours, minutes)) => (hours, minutes),
+        None if time.len() > 2 => time.split_at(time.len() - 2),
+        // only with am/pm, a number alone is no time
+        None if offset.is_some() => (time, "00"),
+        None => return None,
+    };
+
+    if minutes.len() != 2 {
+        return None;
+    }
+    let mut hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+
+    if let Some(offset) = offset {
+        if !(1..=12).contains(&hours) {
+            return None;
+        }
+        hours = hours % 12 + offset;
+    }
+
+    NaiveTime::from_hms_opt(hours, minutes, 0)
+}
+
+/// Parses a duration like `45m`, `1h`, `1h30m`, `45 minutes` or `2 hours`.
+///
+/// Returns `None` for durations too large to be represented.
+#[must_use]
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    let mut rest = input.trim();
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let amount: u64 = rest[..digits].parse().ok()?;
+        rest = rest[digits..].trim_start();
+
+        let unit_length = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let seconds_per_unit = match &rest[..unit_length] {
+            "h" | "hour" | "hours" => 3600,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            _ => return None,
+        };
+        let duration = std::time::Duration::from_secs(amount.checked_mul(seconds_per_unit)?);
+        total = total.checked_add(&Duration::from_std(duration).ok()?)?;
+        rest = rest[unit_length..].trim_start();
+    }
+
+    Some(total)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_util::time;
+
+    #[test]
+    fn test_parse_clock_times() {
+        for input in ["10:30", "1030", "10.30", "10:30am", "10:30 AM"] {
+            assert_eq!(parse_time_input(input), Ok(TimeInput::At(time(10, 30))));
+        }
+
+        assert_eq!(parse_time_input("930"), Ok(TimeInput::At(time(9, 30))));
+        assert_eq!(parse_time_input("2:15pm"), Ok(TimeInput::At(time(14, 15))));
+        assert_eq!(parse_time_input("12am"), Ok(TimeInput::At(time(0, 0))));
+        assert_eq!(parse_time_input("12pm"), Ok(TimeInput::At(time(12, 0))));
+        assert_eq!(parse_time_input("noon"), Ok(TimeInput::At(time(12, 0))));
+
+        for input in ["10", "25:00", "10:5", "13pm", "ten", ""] {
+            assert!(parse_time_input(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_relative_times() {
+        assert_eq!(
+            parse_time_input("-15m"),
+            Ok(TimeInput::Ago(Duration::minutes(15)))
+        );
+        assert_eq!(
+            parse_time_input("15 minutes ago"),
+            Ok(TimeInput::Ago(Duration::minutes(15)))
+        );
+        assert_eq!(
+            parse_time_input("1h 30m ago"),
+            Ok(TimeInput::Ago(Duration::minutes(90)))
+        );
+        assert_eq!(
+            parse_time_input("+45m"),
+            Ok(TimeInput::AfterLastChunk(Duration::minutes(45)))
+        );
+        assert_eq!(parse_time_input("now"), Ok(TimeInput::Now));
+        assert!(parse_time_input("-15 parsecs").is_err());
+        assert!(parse_time_input("+").is_err());
+    }
+
+    #[test]
+    fn test_parse_large_durations() {
+        assert_eq!(parse_duration("100000h"), Some(Duration::hours(100_000)));
+        assert_eq!(parse_duration("9999999999999999h"), None);
+        assert_eq!(parse_duration("99999999999999999999m"), None);
+        assert_eq!(parse_duration("2562047788015h 2562047788015h"), None);
+        assert!(parse_time_input("+9999999999999999h").is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let now = time(12, 0);
+        let last_chunk_end = time(10, 0);
+
+        assert_eq!(TimeInput::Now.resolve(now, last_chunk_end).unwrap(), now);
+        assert_eq!(
+            TimeInput::At(time(11, 0))
+                .resolve(now, last_chunk_end)
+                .unwrap(),
+            time(11, 0)
+        );
+        assert_eq!(
+            TimeInput::Ago(Duration::minutes(15))
+                .resolve(now, last_chunk_end)
+                .unwrap(),
+            time(11, 45)
+        );
+        assert_eq!(
+            TimeInput::AfterLastChunk(Duration::minutes(45))
+                .resolve(now, last_chunk_end)
+                .unwrap(),
+            time(10, 45)
+        );
+    }
+
+    #[test]
+    fn test_resolve_across_midnight() {
+        let now = time(12, 0);
+        let last_chunk_end = time(10, 0);
+
+        assert!(matches!(
+            TimeInput::AfterLastChunk(Duration::hours(20)).resolve(now, last_chunk_end),
+            Err(CommandExecutionError::NoTrackingAfterCurrentTime)
+        ));
+        assert!(matches!(
+            TimeInput::Ago(Duration::hours(13)).resolve(now, last_chunk_end),
+            Err(CommandExecutionError::NoTrackingBeforeCheckIn)
+        ));
+    }
+}
@@ -0,0 +1,320 @@
+use crate::commands::{
+    date_format, optional_time_format, time_format, CommandExecutionError, Day, Period,
+};
+use crate::file_handling;
+use crate::reporting::{
+    day_spans, get_days_of_month, get_days_of_week, is_work, load_available_days,
+};
+use chrono::{NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+
+/// A chunk that was already exported, identified by its day, time range and project.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ExportedChunk {
+    #[serde(with = "date_format")]
+    date: NaiveDate,
+
+    /// Not recorded by earlier versions.
+    #[serde(
+        default,
+        with = "optional_time_format",
+        skip_serializing_if = "Option::is_none"
+    )]
+    start_time: Option<NaiveTime>,
+
+    #[serde(with = "time_format")]
+    end_time: NaiveTime,
+
+    project: String,
+}
+
+impl ExportedChunk {
+    fn is_same(&self, other: &ExportedChunk) -> bool {
+        self.date == other.date
+            && self.end_time == other.end_time
+            && self.project == other.project
+            && self
+                .start_time
+                .is_none_or(|start_time| other.start_time == Some(start_time))
+    }
+
+    fn overlaps(&self, other: &ExportedChunk) -> bool {
+        let start = |chunk: &ExportedChunk| chunk.start_time.unwrap_or(chunk.end_time);
+        self.date == other.date && start(self) < other.end_time && start(other) < self.end_time
+    }
+}
+
+/// Returns the worklogs of all chunks with an issue key in the period that were not
+/// exported yet. With `mark_exported`, they are also recorded as exported.
+///
+/// # Errors
+///
+/// Fails if the record of exported chunks cannot be read or written, or if a chunk starts
+/// at a time that does not exist in the local time zone.
+pub fn export_worklogs(
+    period: &Period,
+    mark_exported: bool,
+) -> Result<Export, CommandExecutionError> {
+    let dates = match period {
+        Period::Day(date) => vec![*date],
+        Period::Week(date) => get_days_of_week(*date),
+        Period::Month(date) => get_days_of_month(*date),
+    };
+    let days = load_available_days(&dates);
+
+    let file_path = file_handling::get_exported_worklogs_file_path()?;
+    let mut exported: Vec<ExportedChunk> = if file_path.exists() {
+        serde_json::from_str(&fs::read_to_string(&file_path)?)?
+    } else {
+        vec![]
+    };
+
+    let mut export = worklogs(&days, &exported, &chrono::Local)?;
+
+    if mark_exported {
+        exported.append(&mut export.chunks);
+        file_handling::create_dir_if_not_exists(&file_path)?;
+        fs::write(file_path, serde_json::to_string(&exported)?)?;
+    }
+
+    Ok(export)
+}
+
+/// Worklogs in the format of the Jira Cloud worklog API.
+#[derive(Debug)]
+pub struct Export {
+    /// One worklog per chunk.
+    pub worklogs: Vec<serde_json::Value>,
+    /// The chunks the worklogs were created from.
+    chunks: Vec<ExportedChunk>,
+    /// Chunks that were left out, and why.
+    pub warnings: Vec<String>,
+}
+
+/// Returns the worklogs of the chunks that were not exported yet.
+///
+/// Each work chunk is booked on its first ticket reference that is an issue key like `ABC-42`.
+/// Chunks overlapping an exported chunk were changed after the export and are left out.
+/// Expects the chunks for each day to be sorted by end time.
+fn worklogs<Tz: TimeZone>(
+    days: &[Day],
+    exported: &[ExportedChunk],
+    timezone: &Tz,
+) -> Result<Export, CommandExecutionError>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let mut export = Export {
+        worklogs: vec![],
+        chunks: vec![],
+        warnings: vec![],
+    };
+
+    for day in days {
+        for span in day_spans(day) {
+            if !is_work(span.project) || span.end <= span.start || span.tickets.is_empty() {
+                continue;
+            }
+
+            let chunk = ExportedChunk {
+                date: day.date,
+                start_time: Some(span.start),
+                end_time: span.end,
+                project: span.project.to_string(),
+            };
+            let range = format!(
+                "{} {}-{} of {}",
+                day.date.format("%Y-%m-%d"),
+                span.start.format("%H:%M"),
+                span.end.format("%H:%M"),
+                span.project
+            );
+
+            if exported.iter().any(|exported| exported.is_same(&chunk)) {
+                continue;
+            }
+            if exported.iter().any(|exported| exported.overlaps(&chunk)) {
+                export.warnings.push(format!(
+                    "The chunk {range} was changed after it was exported, update its worklog manually"
+                ));
+                continue;
+            }
+
+            let Some(issue_key) = span.tickets.iter().find(|ticket| is_issue_key(ticket)) else {
+                export.warnings.push(format!(
+                    "The chunk {range} has no issue key, only {}",
+                    span.tickets.join(", ")
+                ));
+                continue;
+            };
+
+            let started = day.date.and_time(span.start);
+            let started = timezone
+                .from_local_datetime(&started)
+                .earliest()
+                .ok_or(CommandExecutionError::NonexistentLocalTime(started))?;
+
+            let mut worklog = json!({
+                "started": started.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string(),
+                "timeSpentSeconds": (span.end - span.start).num_seconds(),
+            });
+            if let Some(description) = span.description {
+                worklog["comment"] = json!({
+                    "type": "doc",
+                    "version": 1,
+                    "content": [{
+                        "type": "paragraph",
+                        "content": [{ "type": "text", "text": description }],
+                    }],
+                });
+            }
+
+            export.worklogs.push(json!({
+                "issueKey": issue_key,
+                "worklog": worklog,
+            }));
+            export.chunks.push(chunk);
+        }
+    }
+
+    Ok(export)
+}
+
+/// Whether the ticket reference is a Jira issue key like `ABC-42`.
+fn is_issue_key(ticket: &str) -> bool {
+    ticket.split_once('-').is_some_and(|(project, number)| {
+        project.starts_with(|c: char| c.is_ascii_uppercase())
+            && project
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            && !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::commands::Chunk;
+    use crate::test_util::{chunk_until, date, day, time};
+    use chrono::FixedOffset;
+
+    fn chunk(project: &str, description: Option<&str>, tickets: &[&str], hour: u32) -> Chunk {
+        Chunk {
+            description: description.map(str::to_string),
+            tickets: tickets
+                .iter()
+                .map(std::string::ToString::to_string)
+                .collect(),
+            ..chunk_until(project, hour, 0)
+        }
+    }
+
+    fn exported_chunk(
+        start_time: Option<NaiveTime>,
+        end_time: NaiveTime,
+        project: &str,
+    ) -> ExportedChunk {
+        ExportedChunk {
+            date: date(),
+            start_time,
+            end_time,
+            project: project.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_worklogs() {
+        let day = day(vec![
+            chunk("kyoshi", Some("develop feature ABC-42"), &["ABC-42"], 10),
+            chunk("kyoshi", None, &["#123"], 11),
+            chunk("kyoshi", Some("meeting"), &[], 12),
+            chunk("korra", None, &["ABC-7"], 13),
+            chunk("korra", None, &["#7", "ABC-8"], 14),
+        ]);
+
+        // recorded by an earlier version, without the start
+        let exported = vec![exported_chunk(None, time(13, 0), "korra")];
+
+        let export = worklogs(&[day], &exported, &FixedOffset::east_opt(3600).unwrap()).unwrap();
+
+        assert_eq!(
+            export.worklogs,
+            vec![
+                json!({
+                    "issueKey": "ABC-42",
+                    "worklog": {
+                        "started": "2023-11-20T08:00:00.000+0100",
+                        "timeSpentSeconds": 7200,
+                        "comment": {
+                            "type": "doc",
+                            "version": 1,
+                            "content": [{
+                                "type": "paragraph",
+                                "content": [{ "type": "text", "text": "develop feature ABC-42" }],
+                            }],
+                        },
+                    },
+                }),
+                json!({
+                    "issueKey": "ABC-8",
+                    "worklog": {
+                        "started": "2023-11-20T13:00:00.000+0100",
+                        "timeSpentSeconds": 3600,
+                    },
+                }),
+            ]
+        );
+
+        assert_eq!(
+            export.chunks,
+            vec![
+                exported_chunk(Some(time(8, 0)), time(10, 0), "kyoshi"),
+                exported_chunk(Some(time(13, 0)), time(14, 0), "korra"),
+            ]
+        );
+        assert_eq!(
+            export.warnings,
+            vec!["The chunk 2023-11-20 10:00-11:00 of kyoshi has no issue key, only #123"]
+        );
+    }
+
+    #[test]
+    fn test_worklogs_changed_after_export() {
+        let day = day(vec![
+            chunk("kyoshi", None, &["ABC-42"], 10),
+            chunk("kyoshi", None, &["ABC-43"], 12),
+        ]);
+
+        // the first chunk was exported before its end was moved from 09:30 to 10:00
+        let exported = vec![
+            exported_chunk(Some(time(8, 0)), time(9, 30), "kyoshi"),
+            exported_chunk(Some(time(10, 0)), time(12, 0), "kyoshi"),
+        ];
+
+        let export = worklogs(&[day], &exported, &FixedOffset::east_opt(3600).unwrap()).unwrap();
+
+        assert!(export.worklogs.is_empty());
+        assert_eq!(
+            export.warnings,
+            vec![
+                "The chunk 2023-11-20 08:00-10:00 of kyoshi was changed after it was exported, update its worklog manually"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_issue_key() {
+        assert!(is_issue_key("ABC-42"));
+        assert!(is_issue_key("A2B_C-1"));
+        assert!(!is_issue_key("#123"));
+        assert!(!is_issue_key("abc-42"));
+        assert!(!is_issue_key("ABC-"));
+        assert!(!is_issue_key("-42"));
+        assert!(!is_issue_key("ABC-4a"));
+    }
+}